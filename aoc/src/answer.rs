@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// A puzzle answer, keeping enough of its shape to be printed as JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    Lines(Vec<String>),
+    None,
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Lines(_))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+            Answer::None => write!(f, "no solution"),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(n as i128)
+                }
+            }
+        )+
+    };
+}
+
+integer_answer!(i32, i64, i128, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+impl From<day13::Page> for Answer {
+    fn from(page: day13::Page) -> Self {
+        Answer::Lines(page.to_string().lines().map(String::from).collect())
+    }
+}
+
+impl From<day16::BigUint> for Answer {
+    fn from(n: day16::BigUint) -> Self {
+        match n.to_u128().and_then(|n| i128::try_from(n).ok()) {
+            Some(n) => Answer::Integer(n),
+            None => Answer::Text(n.to_string()),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::days::Day;
+use crate::days::Error;
+use crate::params::Params;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter;
+use std::time::Duration;
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            max: samples[n - 1],
+        }
+    }
+}
+
+fn stage(i: usize) -> String {
+    if i == 0 {
+        "parse".to_string()
+    } else {
+        format!("part{}", i)
+    }
+}
+
+/// Runs a day `runs` times and returns the timings of its parse and of each
+/// of its parts, named `parse`, `part1`, `part2`.
+pub fn measure(
+    day: &Day,
+    input: &str,
+    params: &Params,
+    runs: usize,
+) -> Result<Vec<(String, Stats)>, Error> {
+    let mut samples: Vec<Vec<Duration>> = Vec::new();
+
+    for _ in 0..runs {
+        let report = (day.run)(input, params)?;
+        let elapsed = iter::once(report.parse).chain(report.parts.iter().map(|(_, e)| *e));
+
+        for (i, elapsed) in elapsed.enumerate() {
+            if samples.len() == i {
+                samples.push(Vec::new());
+            }
+            samples[i].push(elapsed);
+        }
+    }
+
+    Ok(samples
+        .into_iter()
+        .enumerate()
+        .map(|(i, samples)| (stage(i), Stats::new(samples)))
+        .collect())
+}
+
+/// Median timings saved from an earlier benchmark, one
+/// `<day> <stage> <median in ns>` entry per line.
+#[derive(Default)]
+pub struct Baseline {
+    medians: BTreeMap<(u32, String), Duration>,
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut baseline = Self::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let entry = match fields.as_slice() {
+                [day, stage, nanos] => day
+                    .parse()
+                    .ok()
+                    .zip(nanos.parse().ok())
+                    .map(|(day, nanos)| (day, stage, Duration::from_nanos(nanos))),
+                _ => None,
+            };
+            let (day, stage, median) = entry.ok_or_else(|| {
+                format!(
+                    "line {}: expected `<day> <stage> <median ns>`, found `{}`",
+                    i + 1,
+                    line
+                )
+            })?;
+
+            baseline.insert(day, stage, median);
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn insert(&mut self, day: u32, stage: &str, median: Duration) {
+        self.medians.insert((day, stage.to_string()), median);
+    }
+
+    pub fn get(&self, day: u32, stage: &str) -> Option<Duration> {
+        self.medians.get(&(day, stage.to_string())).copied()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day stage median_ns")?;
+        for ((day, stage), median) in &self.medians {
+            writeln!(f, "{} {} {}", day, stage, median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+/// The change from `baseline` to `median`, in percent of `baseline`.
+pub fn change(baseline: Duration, median: Duration) -> f64 {
+    (median.as_secs_f64() - baseline.as_secs_f64()) / baseline.as_secs_f64() * 100.0
+}
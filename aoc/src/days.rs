@@ -0,0 +1,356 @@
+use crate::answer::Answer;
+use crate::params::ParamError;
+use crate::params::Params;
+use common::stream_lines;
+use common::ParseError;
+use common::Rng;
+use common::StreamError;
+use common::Streaming;
+use std::fmt;
+use std::io::BufRead;
+use std::time::Duration;
+use std::time::Instant;
+
+pub struct Report {
+    pub parse: Duration,
+    pub parts: Vec<(Answer, Duration)>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    Param(ParamError),
+    Packet(day16::PacketError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => e.fmt(f),
+            Error::Param(e) => e.fmt(f),
+            Error::Packet(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<ParamError> for Error {
+    fn from(e: ParamError) -> Self {
+        Error::Param(e)
+    }
+}
+
+impl From<day16::PacketError> for Error {
+    fn from(e: day16::PacketError) -> Self {
+        Error::Packet(e)
+    }
+}
+
+/// A `--<name> <value>` flag a day accepts in place of a hard-coded constant.
+pub struct Flag {
+    pub name: &'static str,
+    pub default: Option<&'static str>,
+    pub help: &'static str,
+}
+
+impl Flag {
+    const fn new(name: &'static str, default: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            default: Some(default),
+            help,
+        }
+    }
+
+    const fn optional(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            default: None,
+            help,
+        }
+    }
+}
+
+pub struct Day {
+    pub number: u32,
+    pub flags: &'static [Flag],
+    pub run: fn(&str, &Params) -> Result<Report, Error>,
+    /// Writes a random, valid input of roughly the given size.
+    pub generate: fn(&mut Rng, usize) -> String,
+    /// Runs the day a line at a time, for days that can.
+    pub stream: Option<Stream>,
+    /// Renders the day's results as files for `aoc export`.
+    pub export: Option<Export>,
+}
+
+/// The files a day exports, as `(file name, contents)`.
+pub type Export = fn(&str, &Params) -> Result<Vec<(String, String)>, Error>;
+
+/// Feeds a day its input a line at a time, reporting the answers so far
+/// every given number of lines, and returns the final answers.
+pub type Stream = fn(
+    &mut dyn BufRead,
+    usize,
+    &mut dyn FnMut(usize, Vec<Answer>),
+) -> Result<Vec<Answer>, StreamError>;
+
+fn streamed<S>(
+    reader: &mut dyn BufRead,
+    every: usize,
+    report: &mut dyn FnMut(usize, Vec<Answer>),
+) -> Result<Vec<Answer>, StreamError>
+where
+    S: Streaming + Default,
+    S::Part1: Into<Answer>,
+    S::Part2: Into<Answer>,
+{
+    let answers = |state: &S| vec![state.part1().into(), state.part2().into()];
+    let mut state = S::default();
+
+    stream_lines(reader, &mut state, |lines, state| {
+        if lines.is_multiple_of(every) {
+            report(lines, answers(state));
+        }
+    })?;
+    Ok(answers(&state))
+}
+
+impl Day {
+    pub fn flag(&self, name: &str) -> Option<&Flag> {
+        self.flags.iter().find(|flag| flag.name == name)
+    }
+
+    /// The day's flag defaults, overridden by those of `flags` it accepts.
+    pub fn params<'a>(&self, flags: impl IntoIterator<Item = (&'a str, &'a str)>) -> Params {
+        let mut params = Params::default();
+        for flag in self.flags {
+            if let Some(default) = flag.default {
+                params.set(flag.name, default);
+            }
+        }
+        for (name, value) in flags {
+            if self.flag(name).is_some() {
+                params.set(name, value);
+            }
+        }
+        params
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+macro_rules! day {
+    (
+        $number:expr, $krate:ident, flags: [$($flag:expr),* $(,)?],
+        |$input:ident, $params:ident| { $($setup:tt)* }
+        parse: |$text:ident| $parse:expr => [$($part:expr),+ $(,)?]
+    ) => {
+        Day {
+            number: $number,
+            flags: &[$($flag),*],
+            run: |input, $params| {
+                $($setup)*
+                let (input, parse) = timed(|| {
+                    let $text = input;
+                    $parse
+                });
+                let $input = &input?;
+                // Parts may fail with `?`, as day 16 does on malformed packets.
+                let parts = vec![$({
+                    let (answer, elapsed) = timed(|| Ok::<_, Error>(Answer::from($part)));
+                    (answer?, elapsed)
+                }),+];
+                Ok(Report { parse, parts })
+            },
+            generate: $krate::generate,
+            stream: None,
+            export: None,
+        }
+    };
+    (
+        $number:expr, $krate:ident, flags: [$($flag:expr),* $(,)?],
+        |$input:ident, $params:ident| { $($setup:tt)* } => [$($part:expr),+ $(,)?]
+    ) => {
+        day!($number, $krate, flags: [$($flag),*], |$input, $params| { $($setup)* }
+            parse: |input| $krate::parse(input) => [$($part),+])
+    };
+    ($number:expr, $krate:ident, stream: $state:ident) => {
+        Day {
+            stream: Some(streamed::<$krate::$state>),
+            ..day!($number, $krate)
+        }
+    };
+    ($number:expr, $krate:ident $(, $part:ident)+) => {
+        day!($number, $krate, flags: [], |input, _params| {} => [$($krate::$part(input)),+])
+    };
+    ($number:expr, $krate:ident) => {
+        day!($number, $krate, part1, part2)
+    };
+}
+
+/// The day 19 `--min-overlap`. Two shared beacons leave a scanner free to
+/// turn about the line through them, so it takes three to align it.
+fn min_overlap(params: &Params) -> Result<usize, ParamError> {
+    let min_overlap = params.get("min-overlap")?;
+    if min_overlap < 3 {
+        return Err(params.error("min-overlap", "scanners must share at least 3 beacons"));
+    }
+    Ok(min_overlap)
+}
+
+/// One answer for each cluster of scanners, or a plain answer if they are
+/// all linked.
+fn per_cluster(clusters: &[day19::Cluster], answer: impl Fn(&day19::Cluster) -> usize) -> Answer {
+    if let [cluster] = clusters {
+        return Answer::from(answer(cluster));
+    }
+
+    let lines = clusters.iter().map(|cluster| {
+        let scanners: Vec<String> = cluster
+            .scanners
+            .iter()
+            .map(|placement| placement.scanner.to_string())
+            .collect();
+        format!(
+            "cluster at scanner {} (scanners {}): {}",
+            cluster.origin,
+            scanners.join(", "),
+            answer(cluster)
+        )
+    });
+    Answer::Lines(lines.collect())
+}
+
+// day 18 is solved in Zig (day18/day18.zig) and is not part of the runner.
+pub const DAYS: &[Day] = &[
+    day!(1, day01, stream: Sweep),
+    day!(2, day02, stream: Course),
+    day!(3, day03),
+    day!(4, day04),
+    day!(5, day05),
+    day!(6, day06, flags: [
+        Flag::new("days", "80,256", "days of spawning for parts 1 and 2"),
+    ], |timers, params| {
+        let (days1, days2) = params.pair("days")?;
+        let overflow = || params.error("days", "too many lanternfish to count");
+    } => [
+        day06::fishes_after(timers, days1).ok_or_else(overflow)?,
+        day06::fishes_after(timers, days2).ok_or_else(overflow)?,
+    ]),
+    day!(7, day07),
+    day!(8, day08, stream: Notes),
+    day!(9, day09),
+    day!(10, day10, stream: Scores),
+    day!(11, day11, flags: [
+        Flag::new("steps", "100", "steps to count flashes over in part 1"),
+    ], |grid, params| {
+        let steps = params.get("steps")?;
+    } => [day11::flashes_after(grid, steps), day11::part2(grid)]),
+    day!(12, day12),
+    day!(13, day13),
+    day!(14, day14, flags: [
+        Flag::new("steps", "10,40", "pair insertion steps for parts 1 and 2"),
+    ], |polymer, params| {
+        let (steps1, steps2) = params.pair("steps")?;
+        let overflow = || params.error("steps", "the polymer grows too long to count");
+    } => [
+        day14::solve(polymer, steps1).ok_or_else(overflow)?,
+        day14::solve(polymer, steps2).ok_or_else(overflow)?,
+    ]),
+    day!(15, day15),
+    Day {
+        export: Some(|input, _params| {
+            let (packet, spans) = day16::parse_with_spans(input)?;
+            Ok(vec![
+                ("infix.txt".to_string(), day16::infix(&packet) + "\n"),
+                ("sexpr.txt".to_string(), day16::sexpr(&packet) + "\n"),
+                ("tree.txt".to_string(), day16::tree(&packet, &spans)),
+            ])
+        }),
+        ..day!(16, day16, flags: [], |packet, _params| {} => [
+            day16::part1(packet),
+            day16::value(packet)?,
+        ])
+    },
+    day!(17, day17, flags: [
+        Flag::optional("target", "target area `x=<a>..<b>,y=<c>..<d>` replacing the input"),
+    ], |target, params| {
+        let custom: Option<day17::Target> = params.optional("target")?;
+    } => [
+        day17::part1(custom.as_ref().unwrap_or(target)),
+        day17::part2(custom.as_ref().unwrap_or(target)),
+    ]),
+    Day {
+        export: Some(|input, params| {
+            let reports = day19::parse(input)?;
+            let clusters = day19::clusters(&reports, min_overlap(params)?);
+            Ok(day19::export::files(&clusters))
+        }),
+        ..day!(19, day19, flags: [
+            Flag::new("min-overlap", "12", "beacons two scanners must both see to be aligned"),
+        ], |clusters, params| {
+            let min_overlap = min_overlap(params)?;
+        }
+        // Clustering is slow and both parts need it, so it is timed as part
+        // of parsing rather than of whichever part runs first.
+        parse: |input| day19::parse(input).map(|reports| day19::clusters(&reports, min_overlap))
+        => [
+            per_cluster(clusters, |cluster| cluster.beacons.len()),
+            per_cluster(clusters, day19::Cluster::largest_distance),
+        ])
+    },
+    day!(20, day20, flags: [
+        Flag::new("steps", "2,50", "enhancement steps for parts 1 and 2"),
+    ], |image, params| {
+        let (steps1, steps2) = params.pair("steps")?;
+    } => [day20::lit_after(image, steps1), day20::lit_after(image, steps2)]),
+    day!(21, day21, flags: [
+        Flag::new("win-score", "1000,21", "scores that win the games of parts 1 and 2"),
+    ], |players, params| {
+        let (score1, score2) = params.pair("win-score")?;
+        let overflow = || params.error("win-score", "too many universes to count");
+    } => [
+        day21::practice_game(players, score1),
+        day21::dirac_game(players, score2).ok_or_else(overflow)?,
+    ]),
+    day!(22, day22, flags: [
+        Flag::new("region", "50", "half-width of the cube counted in part 1"),
+    ], |steps, params| {
+        let region = params.get("region")?;
+    } => [day22::cubes_within(steps, region), day22::part2(steps)]),
+    day!(23, day23, flags: [
+        Flag::optional("rooms", "rooms `<a>,<b>,<c>,<d>`, top to bottom, replacing the input"),
+        Flag::new("unfold", "DD,CB,BA,AC", "rows inserted into each room in part 2"),
+    ], |rooms, params| {
+        let custom = params.parse_with("rooms", day23::parse_rooms)?;
+        let folded = params.get_with("unfold", day23::parse_rooms)?;
+    } => [
+        day23::organize(custom.as_ref().unwrap_or(rooms)),
+        day23::organize(&day23::unfold(custom.as_ref().unwrap_or(rooms), &folded)),
+    ]),
+    Day {
+        export: Some(|input, _params| {
+            let program = day24::parse(input)?;
+            let listing: Vec<String> = program.iter().map(|i| i.to_string()).collect();
+            Ok(vec![
+                ("program.txt".to_string(), listing.join("\n") + "\n"),
+                ("decompiled.txt".to_string(), day24::decompile(&program)),
+            ])
+        }),
+        ..day!(24, day24, largest, smallest)
+    },
+    day!(25, day25, part1),
+];
+
+pub fn find(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
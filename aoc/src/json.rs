@@ -0,0 +1,49 @@
+use crate::answer::Answer;
+use std::fmt::Write;
+use std::time::Duration;
+
+fn string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn answer(out: &mut String, answer: &Answer) {
+    match answer {
+        Answer::Integer(n) => write!(out, "{}", n).unwrap(),
+        Answer::Text(s) => string(out, s),
+        Answer::Lines(lines) => {
+            out.push('[');
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                string(out, line);
+            }
+            out.push(']');
+        }
+        Answer::None => out.push_str("null"),
+    }
+}
+
+/// Renders a day's results as a single-line JSON object, e.g.
+/// `{"day":1,"part1":1466,"part2":1491,"elapsed_ms":0.12}`.
+pub fn day(number: u32, answers: &[Answer], elapsed: Duration) -> String {
+    let mut out = format!("{{\"day\":{}", number);
+    for (i, a) in answers.iter().enumerate() {
+        write!(out, ",\"part{}\":", i + 1).unwrap();
+        answer(&mut out, a);
+    }
+    write!(out, ",\"elapsed_ms\":{}}}", elapsed.as_secs_f64() * 1000.0).unwrap();
+    out
+}
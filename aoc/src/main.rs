@@ -0,0 +1,405 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+use std::process;
+use std::slice;
+use std::time::Duration;
+
+mod answer;
+mod bench;
+mod days;
+mod json;
+mod params;
+
+use bench::Baseline;
+use common::Rng;
+use common::StreamError;
+use days::Day;
+use days::Error;
+use params::Params;
+
+const USAGE: &str = "\
+usage: aoc run <day|all> [--input <path>] [--format text|json] [--<flag> <value>...]
+       aoc bench <day|all> [--input <path>] [--runs <n>] [--save <path>]
+                 [--baseline <path>] [--threshold <percent>] [--<flag> <value>...]
+       aoc export <day> --out <dir> [--input <path>] [--<flag> <value>...]
+       aoc stream <day> [--input <path>|-] [--every <lines>]
+       aoc generate <day> [--seed <n>] [--size <n>]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+struct BenchOptions {
+    runs: usize,
+    threshold: f64,
+    baseline: Option<Baseline>,
+}
+
+fn default_input(day: &Day) -> String {
+    format!("day{:02}/input", day.number)
+}
+
+fn unknown_flag(day: &Day, name: &str) -> String {
+    let flags: Vec<String> = day
+        .flags
+        .iter()
+        .map(|flag| match flag.default {
+            Some(default) => format!("  --{} (default {}): {}", flag.name, default, flag.help),
+            None => format!("  --{}: {}", flag.name, flag.help),
+        })
+        .collect();
+
+    if flags.is_empty() {
+        format!("day {} takes no flags, got --{}", day.number, name)
+    } else {
+        format!(
+            "day {} takes no --{} flag; it takes:\n{}",
+            day.number,
+            name,
+            flags.join("\n")
+        )
+    }
+}
+
+fn describe(day: &Day, input_path: &str, e: Error) -> String {
+    match e {
+        Error::Parse(e) => format!("{}: {}", input_path, e),
+        Error::Param(e) => format!("day {}: {}", day.number, e),
+        Error::Packet(e) => format!("{}: {}", input_path, e),
+    }
+}
+
+fn run_day(
+    day: &Day,
+    input_path: &str,
+    params: &Params,
+    format: Format,
+) -> Result<Duration, String> {
+    let input = fs::read_to_string(input_path)
+        .map_err(|e| format!("error reading {}: {}", input_path, e))?;
+    let report = (day.run)(&input, params).map_err(|e| describe(day, input_path, e))?;
+    let total = report.parse
+        + report
+            .parts
+            .iter()
+            .map(|(_, elapsed)| *elapsed)
+            .sum::<Duration>();
+
+    if format == Format::Json {
+        let answers: Vec<_> = report.parts.into_iter().map(|(answer, _)| answer).collect();
+        println!("{}", json::day(day.number, &answers, total));
+        return Ok(total);
+    }
+
+    println!("day {}", day.number);
+    println!("  parse ({:?})", report.parse);
+    for (i, (answer, elapsed)) in report.parts.iter().enumerate() {
+        if answer.is_multiline() {
+            println!("  part {} ({:?}):\n{}", i + 1, elapsed, answer);
+        } else {
+            println!("  part {}: {} ({:?})", i + 1, answer, elapsed);
+        }
+    }
+
+    Ok(total)
+}
+
+fn bench_day(
+    day: &Day,
+    input_path: &str,
+    params: &Params,
+    options: &BenchOptions,
+    results: &mut Baseline,
+) -> Result<usize, String> {
+    let input = fs::read_to_string(input_path)
+        .map_err(|e| format!("error reading {}: {}", input_path, e))?;
+    let stages = bench::measure(day, &input, params, options.runs)
+        .map_err(|e| describe(day, input_path, e))?;
+    let mut regressions = 0;
+
+    println!("day {}", day.number);
+    for (stage, stats) in &stages {
+        print!(
+            "  {:<6} min {:>12?}  median {:>12?}  max {:>12?}",
+            stage, stats.min, stats.median, stats.max
+        );
+
+        let baseline = options.baseline.as_ref();
+        if let Some(median) = baseline.and_then(|baseline| baseline.get(day.number, stage)) {
+            let change = bench::change(median, stats.median);
+            print!("  {:+.1}%", change);
+            if change > options.threshold {
+                print!(" REGRESSION");
+                regressions += 1;
+            }
+        }
+        println!();
+
+        results.insert(day.number, stage, stats.median);
+    }
+
+    Ok(regressions)
+}
+
+fn generate(args: &mut slice::Iter<String>) -> Result<(), String> {
+    let which = args.next().ok_or(USAGE)?;
+    let number: u32 = which
+        .parse()
+        .map_err(|_| format!("invalid day: {}\n{}", which, USAGE))?;
+    let day = days::find(number).ok_or(format!("no solution for day {}", number))?;
+    let mut seed = 0;
+    let mut size = 10;
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+
+        match arg.as_str() {
+            "--seed" => {
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed: {}", value))?
+            }
+            "--size" => {
+                size = value
+                    .parse()
+                    .map_err(|_| format!("invalid size: {}", value))?
+            }
+            _ => return Err(format!("unexpected argument: {}\n{}", arg, USAGE)),
+        }
+    }
+
+    print!("{}", (day.generate)(&mut Rng::new(seed), size));
+    Ok(())
+}
+
+/// Runs a day over its input a line at a time, reading stdin for `-`, and
+/// prints the answers so far every `--every` lines.
+fn stream(args: &mut slice::Iter<String>) -> Result<(), String> {
+    let which = args.next().ok_or(USAGE)?;
+    let number: u32 = which
+        .parse()
+        .map_err(|_| format!("invalid day: {}\n{}", which, USAGE))?;
+    let day = days::find(number).ok_or(format!("no solution for day {}", number))?;
+    let stream = day
+        .stream
+        .ok_or(format!("day {} cannot be streamed", number))?;
+    let mut input_path = default_input(day);
+    let mut every = 100_000;
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+
+        match arg.as_str() {
+            "--input" => input_path = value.clone(),
+            "--every" => {
+                every = value
+                    .parse()
+                    .ok()
+                    .filter(|&every| every > 0)
+                    .ok_or_else(|| format!("invalid number of lines: {}", value))?
+            }
+            _ => return Err(format!("unexpected argument: {}\n{}", arg, USAGE)),
+        }
+    }
+
+    let mut reader: Box<dyn BufRead> = if input_path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = fs::File::open(&input_path)
+            .map_err(|e| format!("error reading {}: {}", input_path, e))?;
+        Box::new(BufReader::new(file))
+    };
+
+    println!("day {}", day.number);
+    let answers = stream(&mut reader, every, &mut |lines, answers| {
+        let answers: Vec<String> = answers
+            .iter()
+            .enumerate()
+            .map(|(i, answer)| format!("part {}: {}", i + 1, answer))
+            .collect();
+        println!("  after {} lines: {}", lines, answers.join(", "));
+    })
+    .map_err(|e| match e {
+        StreamError::Io(e) => format!("error reading {}: {}", input_path, e),
+        StreamError::Parse(e) => format!("{}: {}", input_path, e),
+    })?;
+
+    for (i, answer) in answers.iter().enumerate() {
+        println!("  part {}: {}", i + 1, answer);
+    }
+    Ok(())
+}
+
+/// Writes a day's export files into `out_dir`, creating it if needed.
+fn export_day(day: &Day, input_path: &str, params: &Params, out_dir: &str) -> Result<(), String> {
+    let export = day
+        .export
+        .ok_or(format!("day {} has nothing to export", day.number))?;
+    let input = fs::read_to_string(input_path)
+        .map_err(|e| format!("error reading {}: {}", input_path, e))?;
+    let files = export(&input, params).map_err(|e| describe(day, input_path, e))?;
+
+    fs::create_dir_all(out_dir).map_err(|e| format!("error creating {}: {}", out_dir, e))?;
+    for (name, contents) in files {
+        let path = Path::new(out_dir).join(name);
+        fs::write(&path, contents)
+            .map_err(|e| format!("error writing {}: {}", path.display(), e))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter();
+
+    let command = match args.next().map(String::as_str) {
+        Some("generate") => return generate(&mut args),
+        Some("stream") => return stream(&mut args),
+        Some(command @ ("run" | "bench" | "export")) => command,
+        Some(command) => return Err(format!("unknown command: {}\n{}", command, USAGE)),
+        None => return Err(USAGE.to_string()),
+    };
+
+    let which = args.next().ok_or(USAGE)?;
+    let mut input_path = None;
+    let mut flags = Vec::new();
+    let mut format = Format::Text;
+    let mut bench_options = BenchOptions {
+        runs: 10,
+        threshold: 10.0,
+        baseline: None,
+    };
+    let mut save_path = None;
+    let mut out_dir = None;
+
+    while let Some(arg) = args.next() {
+        let name = match arg.strip_prefix("--") {
+            Some(name) => name,
+            None => return Err(format!("unexpected argument: {}\n{}", arg, USAGE)),
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for --{}", name))?;
+
+        match (command, name) {
+            (_, "input") => input_path = Some(value),
+            ("run", "format") => {
+                format = match value.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(format!("unknown format: {}\n{}", value, USAGE)),
+                }
+            }
+            ("bench", "runs") => {
+                bench_options.runs = value
+                    .parse()
+                    .ok()
+                    .filter(|&runs| runs > 0)
+                    .ok_or_else(|| format!("invalid number of runs: {}", value))?
+            }
+            ("bench", "threshold") => {
+                bench_options.threshold = value
+                    .parse()
+                    .map_err(|_| format!("invalid threshold: {}", value))?
+            }
+            ("bench", "baseline") => {
+                let baseline = fs::read_to_string(value)
+                    .map_err(|e| format!("error reading {}: {}", value, e))?;
+                bench_options.baseline =
+                    Some(Baseline::parse(&baseline).map_err(|e| format!("{}: {}", value, e))?);
+            }
+            ("bench", "save") => save_path = Some(value),
+            ("export", "out") => out_dir = Some(value),
+            _ => flags.push((name, value.as_str())),
+        }
+    }
+
+    let selected: Vec<(&Day, String)> = if which == "all" {
+        if command == "export" {
+            return Err("export needs a single day".to_string());
+        }
+        if input_path.is_some() {
+            return Err("--input cannot be used with `all`".to_string());
+        }
+
+        for &(name, _) in &flags {
+            if !days::DAYS.iter().any(|day| day.flag(name).is_some()) {
+                return Err(format!("no day takes a --{} flag", name));
+            }
+        }
+
+        days::DAYS
+            .iter()
+            .map(|day| (day, default_input(day)))
+            .collect()
+    } else {
+        let number: u32 = which
+            .parse()
+            .map_err(|_| format!("invalid day: {}\n{}", which, USAGE))?;
+        let day = days::find(number).ok_or(format!("no solution for day {}", number))?;
+
+        if let Some(&(name, _)) = flags.iter().find(|(name, _)| day.flag(name).is_none()) {
+            return Err(unknown_flag(day, name));
+        }
+
+        let input_path = input_path.cloned().unwrap_or_else(|| default_input(day));
+        vec![(day, input_path)]
+    };
+
+    if command == "export" {
+        let (day, input_path) = &selected[0];
+        let out_dir = out_dir.ok_or(format!("missing --out <dir>\n{}", USAGE))?;
+        return export_day(day, input_path, &day.params(flags), out_dir);
+    }
+
+    if command == "bench" {
+        let mut results = Baseline::default();
+        let mut regressions = 0;
+
+        for (day, input_path) in &selected {
+            let params = day.params(flags.iter().copied());
+            regressions += bench_day(day, input_path, &params, &bench_options, &mut results)?;
+        }
+
+        if let Some(path) = save_path {
+            fs::write(path, results.to_string())
+                .map_err(|e| format!("error writing {}: {}", path, e))?;
+        }
+        if regressions > 0 {
+            return Err(format!(
+                "{} stage(s) regressed by more than {}%",
+                regressions, bench_options.threshold
+            ));
+        }
+        return Ok(());
+    }
+
+    let mut total = Duration::ZERO;
+    for (day, input_path) in &selected {
+        let params = day.params(flags.iter().copied());
+        total += run_day(day, input_path, &params, format)?;
+    }
+    if which == "all" && format == Format::Text {
+        println!("total: {:?}", total);
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(e) = run(&args) {
+        eprintln!("aoc: {}", e);
+        process::exit(1);
+    }
+}
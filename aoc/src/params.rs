@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A value given for a command line flag that the day could not use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamError {
+    pub name: String,
+    pub value: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value `{}` for --{}: {}",
+            self.value, self.name, self.message
+        )
+    }
+}
+
+impl error::Error for ParamError {}
+
+/// Flag values for a single day, keyed by flag name without the leading `--`.
+#[derive(Default, Clone)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    /// Parses the value of `name` with `f`, if the flag has a value.
+    pub fn parse_with<T, E: fmt::Display>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, ParamError> {
+        self.values
+            .get(name)
+            .map(|value| {
+                f(value).map_err(|e| ParamError {
+                    name: name.to_string(),
+                    value: value.clone(),
+                    message: e.to_string(),
+                })
+            })
+            .transpose()
+    }
+
+    /// An error for the value of `name`, which parsed but which the day
+    /// cannot use, such as one making an answer overflow.
+    pub fn error(&self, name: &str, message: &str) -> ParamError {
+        ParamError {
+            name: name.to_string(),
+            value: self.values.get(name).cloned().unwrap_or_default(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Like `parse_with`, for flags that always have a value.
+    pub fn get_with<T, E: fmt::Display>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&str) -> Result<T, E>,
+    ) -> Result<T, ParamError> {
+        self.parse_with(name, f)?.ok_or_else(|| ParamError {
+            name: name.to_string(),
+            value: String::new(),
+            message: "missing value".to_string(),
+        })
+    }
+
+    pub fn optional<T>(&self, name: &str) -> Result<Option<T>, ParamError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.parse_with(name, str::parse)
+    }
+
+    pub fn get<T>(&self, name: &str) -> Result<T, ParamError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.get_with(name, str::parse)
+    }
+
+    /// Reads `<part 1>,<part 2>`, or a single value used for both parts.
+    pub fn pair<T>(&self, name: &str) -> Result<(T, T), ParamError>
+    where
+        T: FromStr + Clone,
+        T::Err: fmt::Display,
+    {
+        self.get_with(name, |value| {
+            let values = value
+                .split(',')
+                .map(|v| v.trim().parse().map_err(|e: T::Err| e.to_string()))
+                .collect::<Result<Vec<T>, _>>()?;
+
+            match values.as_slice() {
+                [both] => Ok((both.clone(), both.clone())),
+                [part1, part2] => Ok((part1.clone(), part2.clone())),
+                _ => Err("expected one value, or two separated by a comma".to_string()),
+            }
+        })
+    }
+}
@@ -0,0 +1,342 @@
+use common::Rng;
+use day24::alu::accepts;
+use day24::alu::run;
+use day24::alu::AluError;
+use day24::alu::Registers;
+use day24::compile::compile;
+use day24::search::first_accepted;
+use std::time::Duration;
+use std::time::Instant;
+
+fn program(text: &str) -> Vec<day24::Instruction> {
+    day24::parse(text).unwrap()
+}
+
+#[test]
+fn negate() {
+    let registers = run(&program("inp x\nmul x -1"), [7]).unwrap();
+    assert_eq!(registers.x, -7);
+}
+
+#[test]
+fn binary_digits() {
+    let program = program(
+        "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\n\
+         add x w\nmod x 2\ndiv w 2\nmod w 2",
+    );
+    assert_eq!(
+        run(&program, [13]),
+        Ok(Registers {
+            w: 1,
+            x: 1,
+            y: 0,
+            z: 1
+        })
+    );
+}
+
+#[test]
+fn three_times_larger() {
+    let program = program("inp z\ninp x\nmul z 3\neql z x");
+    assert_eq!(run(&program, [2, 6]).unwrap().z, 1);
+    assert_eq!(run(&program, [2, 7]).unwrap().z, 0);
+}
+
+#[test]
+fn crashes() {
+    assert_eq!(
+        run(&program("inp w\ndiv w x"), [1]),
+        Err(AluError::DivisionByZero { at: 1 })
+    );
+    assert_eq!(
+        run(&program("add w 3\nmod w 0"), []),
+        Err(AluError::DivisionByZero { at: 1 })
+    );
+    assert_eq!(
+        run(&program("add w -3\nmod w 2"), []),
+        Err(AluError::NegativeModulo { at: 1 })
+    );
+    assert_eq!(
+        run(&program("add w 3\nmod w -2"), []),
+        Err(AluError::NegativeModulo { at: 1 })
+    );
+    assert_eq!(
+        run(&program("inp w\ninp x"), [1]),
+        Err(AluError::MissingInput { at: 1 })
+    );
+    assert_eq!(
+        AluError::NegativeModulo { at: 1 }.to_string(),
+        "instruction 1: negative modulo"
+    );
+}
+
+#[test]
+fn monad() {
+    let program = program(include_str!("../../day24/input"));
+    let (largest, smallest) = (day24::part1(&program), day24::part2(&program));
+
+    assert_eq!(accepts(&program, largest), Ok(true));
+    assert_eq!(accepts(&program, smallest), Ok(true));
+    assert_eq!(accepts(&program, largest + 1), Ok(false));
+    assert_eq!(accepts(&program, smallest - 1), Ok(false));
+    assert_eq!(accepts(&program, 10_000_000_000_000), Ok(false));
+    assert_eq!(accepts(&program, 0), Ok(false));
+    assert_eq!(accepts(&program, -5), Ok(false));
+    assert_eq!(accepts(&program, i64::MIN), Ok(false));
+}
+
+#[test]
+fn compiled_accepts() {
+    let program = program(include_str!("../../day24/input"));
+    let compiled = compile(&program, 1..=9);
+    let (largest, smallest) = (day24::part1(&program), day24::part2(&program));
+    let model_numbers = [largest, smallest, largest + 1, smallest - 1, 0, -5];
+
+    for model_number in model_numbers {
+        assert_eq!(
+            compiled.accepts(model_number),
+            accepts(&program, model_number)
+        );
+    }
+    assert_eq!(
+        compiled.accepts_all(&model_numbers),
+        [
+            Ok(true),
+            Ok(true),
+            Ok(false),
+            Ok(false),
+            Ok(false),
+            Ok(false)
+        ]
+    );
+    let two_inputs = compile(&day24::parse("inp w\ninp x").unwrap(), 1..=9);
+    assert_eq!(
+        two_inputs.accepts_all(&[12, 3, 45]),
+        [Ok(true), Err(AluError::MissingInput { at: 1 }), Ok(true)]
+    );
+}
+
+/// The fastest of a few runs of `f`, to keep other tests from skewing it.
+fn fastest(mut f: impl FnMut()) -> Duration {
+    (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+// Wall-clock timing is unreliable on a loaded machine, so this only runs on
+// request: `cargo test --release --test alu -- --ignored`.
+#[test]
+#[ignore]
+fn compiled_speedup() {
+    let program = program(include_str!("../../day24/input"));
+    let compiled = compile(&program, 1..=9);
+    let mut rng = Rng::new(0);
+    let inputs: Vec<Vec<i64>> = (0..200_000)
+        .map(|_| (0..14).map(|_| rng.between(1, 9)).collect())
+        .collect();
+
+    let mut expected = Vec::new();
+    let interpreted = fastest(|| {
+        expected = inputs
+            .iter()
+            .map(|input| run(&program, input.iter().copied()))
+            .collect()
+    });
+    let mut results = Vec::new();
+    let compiled_time = fastest(|| results = compiled.run_all(&inputs));
+
+    assert_eq!(results, expected);
+    let speedup = interpreted.as_secs_f64() / compiled_time.as_secs_f64();
+    assert!(speedup >= 10.0, "only {:.1} times faster", speedup);
+}
+
+#[test]
+fn search_matches_stack_pairing() {
+    let program = program(include_str!("../../day24/input"));
+    let ascending = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let descending = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+
+    assert!(day24::block_parameters(&program).is_some());
+    assert_eq!(
+        first_accepted(&program, descending),
+        Some(day24::part1(&program))
+    );
+    assert_eq!(
+        first_accepted(&program, ascending),
+        Some(day24::part2(&program))
+    );
+}
+
+#[test]
+fn reordered_monad() {
+    let mut program = program(include_str!("../../day24/input"));
+    let expected = day24::model_numbers(&program);
+    // `inp w` and `mul x 0` commute, but break the block template.
+    for block in program.chunks_mut(18) {
+        block.swap(0, 1);
+    }
+
+    assert_eq!(day24::block_parameters(&program), None);
+    assert_eq!(day24::model_numbers(&program), expected);
+}
+
+#[test]
+fn any_program() {
+    let difference = program("inp w\ninp x\nadd z w\nmul x -1\nadd z x\nadd z -3");
+    assert_eq!(day24::model_numbers(&difference), Some((41, 96)));
+
+    let never = program("inp w\nadd z w");
+    assert_eq!(day24::model_numbers(&never), None);
+    assert_eq!(day24::model_numbers(&program("add z 0")), None);
+
+    // Accepting every input: 18 digits fit in an i64, 19 nines do not.
+    let inputs = |count: usize| program(&vec!["inp w"; count].join("\n"));
+    assert_eq!(
+        day24::model_numbers(&inputs(18)),
+        Some((111_111_111_111_111_111, 999_999_999_999_999_999))
+    );
+    assert_eq!(
+        day24::smallest(&inputs(19)),
+        Some(1_111_111_111_111_111_111)
+    );
+    assert_eq!(day24::largest(&inputs(19)), None);
+    assert_eq!(day24::model_numbers(&inputs(20)), None);
+}
+
+#[test]
+fn compiled_matches_interpreter() {
+    let mut rng = Rng::new(0);
+    let programs = (0..10)
+        .map(|seed| day24::generate(&mut Rng::new(seed), 0))
+        .chain([include_str!("../../day24/input").to_string()]);
+
+    for text in programs {
+        let program = program(&text);
+        let compiled = compile(&program, 1..=9);
+        let inputs: Vec<Vec<i64>> = (0..100)
+            .map(|_| (0..14).map(|_| rng.between(1, 9)).collect())
+            .collect();
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| run(&program, input.iter().copied()))
+            .collect();
+
+        assert!(compiled.len() < program.len() / 2, "{:?}", compiled);
+        assert_eq!(compiled.run_all(&inputs), expected);
+        for (input, expected) in inputs.iter().zip(&expected) {
+            assert_eq!(&compiled.run(input), expected);
+        }
+    }
+}
+
+#[test]
+fn compiled_crashes() {
+    let programs = [
+        "inp w\ndiv w x",
+        "inp w\nmod w x",
+        "inp x\nadd x -5\nmod x 3",
+        "inp w\nmul w -1\nmod x w",
+        "inp w\ndiv w 0\ninp x",
+        "inp w\ninp x\nadd z x",
+        "add w -3\nmod w 2\ninp x",
+    ];
+
+    for text in programs {
+        let program = program(text);
+        let compiled = compile(&program, 1..=9);
+        let inputs: Vec<Vec<i64>> = (0..=3)
+            .flat_map(|len| (1..=9).map(move |digit| vec![digit; len]))
+            .collect();
+
+        for input in &inputs {
+            let expected = run(&program, input.iter().copied());
+            assert_eq!(compiled.run(input), expected, "{:?} on {:?}", text, input);
+        }
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| run(&program, input.iter().copied()))
+            .collect();
+        assert_eq!(compiled.run_all(&inputs), expected, "{:?}", text);
+    }
+}
+
+#[test]
+fn dead_stores() {
+    // Only the input and the final copy of it into `x` are left.
+    let compiled = compile(&program("inp w\nmul x 0\nadd x 3\nmul x 0\nadd x w"), 1..=9);
+    assert_eq!(compiled.len(), 2);
+    assert_eq!(
+        compiled.run(&[4]),
+        Ok(Registers {
+            w: 4,
+            x: 4,
+            y: 0,
+            z: 0
+        })
+    );
+}
+
+#[test]
+fn compiled_division_by_constants() {
+    let mut rng = Rng::new(0);
+    for divisor in [1, 2, 3, 7, 26, 1000, (1 << 31) + 1, i64::MAX - 1, i64::MAX] {
+        let program = program(&format!("inp w\ndiv w {0}\ninp x\nmod x {0}", divisor));
+        let compiled = compile(&program, 0..=i64::MAX);
+        let mut values = vec![0, 1, divisor - 1, divisor, i64::MAX - 1, i64::MAX];
+        values.extend((0..100).map(|_| rng.between(0, i64::MAX - 1)));
+
+        for value in values {
+            let input = [value, value];
+            assert_eq!(
+                compiled.run(&input),
+                run(&program, input),
+                "{} by {}",
+                value,
+                divisor
+            );
+        }
+    }
+}
+
+#[test]
+fn display_round_trips() {
+    let texts = (0..5)
+        .map(|seed| day24::generate(&mut Rng::new(seed), 0))
+        .chain([include_str!("../../day24/input").to_string()]);
+
+    for text in texts {
+        let listing: Vec<String> = program(&text).iter().map(|i| i.to_string()).collect();
+        assert_eq!(listing.join("\n") + "\n", text);
+    }
+}
+
+#[test]
+fn decompile() {
+    let program = program(include_str!("../../day24/input"));
+    let decompiled = day24::decompile(&program);
+
+    assert_eq!(decompiled.lines().count(), 2 * 14);
+    assert!(decompiled.starts_with(
+        "block 1 (instructions 0..18): a = 1, b = 12, c = 4\n    z = z * 26 + w1 + 4\n"
+    ));
+    assert!(decompiled.contains(
+        "block 6 (instructions 90..108): a = 26, b = -10, c = 7\n    \
+         z = if z % 26 - 10 == w6 { z / 26 } else { z / 26 * 26 + w6 + 7 }\n"
+    ));
+}
+
+#[test]
+fn decompile_other_programs() {
+    let program = program("add z 1\ninp w\nadd z w\ninp x\nmul z x");
+    assert_eq!(
+        day24::decompile(&program),
+        "before the first input (instructions 0..1):\n    add z 1\n\
+         block 1 (instructions 1..3):\n    inp w\n    add z w\n\
+         block 2 (instructions 3..5):\n    inp x\n    mul z x\n"
+    );
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::aoc;
+use common::temp_path;
+use std::fs;
+
+#[test]
+fn save_and_compare_baseline() {
+    let path = temp_path("bench");
+    let path = path.to_str().unwrap();
+    let bench = ["bench", "6", "--input", "day06/test_input", "--runs", "3"];
+
+    let output = aoc(&[&bench[..], &["--save", path]].concat());
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for stage in ["parse ", "part1 ", "part2 "] {
+        assert!(stdout.contains(&format!("  {} min ", stage)), "{}", stdout);
+    }
+
+    let baseline = fs::read_to_string(path).unwrap();
+    let stages: Vec<Vec<&str>> = baseline
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.split(' ').collect())
+        .collect();
+    assert_eq!(stages.len(), 3);
+    assert!(stages
+        .iter()
+        .all(|fields| fields.len() == 3 && fields[0] == "6"));
+
+    let output = aoc(&[&bench[..], &["--baseline", path, "--threshold", "1e9"]].concat());
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = aoc(&[&bench[..], &["--baseline", path, "--threshold", "-100"]].concat());
+    fs::remove_file(path).unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("REGRESSION"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("3 stage(s) regressed by more than -100%"));
+}
+
+#[test]
+fn invalid_baseline() {
+    let path = temp_path("bench-invalid");
+    fs::write(&path, "# day stage median_ns\n6 parse fast\n").unwrap();
+
+    let output = aoc(&["bench", "6", "--baseline", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("line 2: expected `<day> <stage> <median ns>`, found `6 parse fast`"));
+}
@@ -0,0 +1,428 @@
+use common::ParseError;
+use common::Rng;
+use day16::BigUint;
+use day16::BitReader;
+use day16::EncodeError;
+use day16::LengthType;
+use day16::Packet;
+use day16::PacketError;
+use day16::Parser;
+use day16::Value;
+
+fn literal(version: usize, value: usize) -> Packet {
+    Packet {
+        version,
+        type_id: 4,
+        val: Value::Literal(BigUint::from(value)),
+    }
+}
+
+fn operator(version: usize, type_id: usize, sub_packets: Vec<Packet>) -> Packet {
+    Packet {
+        version,
+        type_id,
+        val: Value::Operator(sub_packets),
+    }
+}
+
+#[test]
+fn encodes_examples() {
+    assert_eq!(
+        literal(6, 2021).to_hex(|_| LengthType::Bits),
+        Ok("D2FE28".to_string())
+    );
+
+    let packet = operator(1, 6, vec![literal(6, 10), literal(2, 20)]);
+    assert_eq!(
+        packet.to_hex(|_| LengthType::Bits),
+        Ok("38006F45291200".to_string())
+    );
+
+    let packet = operator(7, 3, vec![literal(2, 1), literal(4, 2), literal(1, 3)]);
+    assert_eq!(
+        packet.to_hex(|_| LengthType::Count),
+        Ok("EE00D40C823060".to_string())
+    );
+}
+
+#[test]
+fn round_trips() {
+    let strategies: [fn(&Packet) -> LengthType; 3] = [
+        |_| LengthType::Bits,
+        |_| LengthType::Count,
+        |packet| {
+            if packet.version % 2 == 0 {
+                LengthType::Bits
+            } else {
+                LengthType::Count
+            }
+        },
+    ];
+
+    for seed in 0..100 {
+        let packet = day16::generate_packet(&mut Rng::new(seed), 4);
+        for length_type in strategies {
+            let bits = packet.to_bits(length_type).unwrap();
+            assert_eq!(
+                Parser::new(BitReader::from_bits(&bits)).parse_packet(),
+                Ok(packet.clone()),
+                "seed {}",
+                seed
+            );
+
+            let hex = packet.to_hex(length_type).unwrap();
+            assert_eq!(day16::parse(&hex).unwrap(), packet, "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn encode_errors() {
+    assert_eq!(
+        literal(8, 1).to_hex(|_| LengthType::Bits),
+        Err(EncodeError::TooLarge {
+            field: "version",
+            value: 8,
+            bits: 3
+        })
+    );
+    assert_eq!(
+        operator(0, 4, vec![]).to_hex(|_| LengthType::Bits),
+        Err(EncodeError::TypeMismatch { type_id: 4 })
+    );
+
+    let many = operator(0, 0, (0..3000).map(|_| literal(0, 0)).collect());
+    assert_eq!(
+        many.to_hex(|_| LengthType::Count).unwrap_err().to_string(),
+        "sub-packet count 3000 does not fit in 11 bits"
+    );
+    assert_eq!(
+        many.to_hex(|_| LengthType::Bits).unwrap_err().to_string(),
+        "sub-packet length 33000 does not fit in 15 bits"
+    );
+}
+
+#[test]
+fn truncated() {
+    for seed in 0..20 {
+        let packet = day16::generate_packet(&mut Rng::new(seed), 3);
+        let bits = packet.to_bits(|_| LengthType::Bits).unwrap();
+        for len in 0..bits.len() {
+            assert_eq!(
+                Parser::new(BitReader::from_bits(&bits[..len])).parse_packet(),
+                Err(PacketError::UnexpectedEnd { offset: len }),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    assert_eq!(
+        day16::parse("38006F4"),
+        Err(ParseError {
+            day: 16,
+            line: 1,
+            column: 8,
+            text: String::new(),
+            message: "bit 28: unexpected end of transmission".to_string(),
+        })
+    );
+}
+
+#[test]
+fn length_overrun() {
+    // 38006F45291200 with its 27-bit sub-packet length cut to 20.
+    let mut bits = operator(1, 6, vec![literal(6, 10), literal(2, 20)])
+        .to_bits(|_| LengthType::Bits)
+        .unwrap();
+    for (i, bit) in bits[7..22].iter_mut().enumerate() {
+        *bit = (20 >> (14 - i)) as u8 & 1;
+    }
+    assert_eq!(
+        Parser::new(BitReader::from_bits(&bits)).parse_packet(),
+        Err(PacketError::LengthOverrun {
+            offset: 22,
+            length: 20
+        })
+    );
+
+    assert_eq!(
+        day16::parse("38005345291200"),
+        Err(ParseError {
+            day: 16,
+            line: 1,
+            column: 6,
+            text: "3".to_string(),
+            message: "bit 22: sub-packets run past their length of 20 bits".to_string(),
+        })
+    );
+}
+
+/// `depth` sum operators of one sub-packet each, around the literal 7.
+fn nested_bits(depth: usize) -> Vec<u8> {
+    let header = "000000100000000001";
+    let bits = header.repeat(depth) + "00010000111";
+    bits.bytes().map(|b| b - b'0').collect()
+}
+
+#[test]
+fn nesting_depth() {
+    let bits = nested_bits(day16::MAX_DEPTH - 1);
+    let packet = Parser::new(BitReader::from_bits(&bits))
+        .parse_packet()
+        .unwrap();
+    assert_eq!(packet.evaluate(), Ok(7));
+    assert_eq!(day16::tree(&packet, &[]).lines().count(), day16::MAX_DEPTH);
+
+    let bits = nested_bits(day16::MAX_DEPTH);
+    assert_eq!(
+        Parser::new(BitReader::from_bits(&bits)).parse_packet(),
+        Err(PacketError::TooDeep {
+            offset: 18 * day16::MAX_DEPTH
+        })
+    );
+
+    // Far deeper nesting fails the same way rather than overflowing the stack.
+    let hex = nested_bits(100_000)
+        .chunks(4)
+        .map(|digit| {
+            let digit = digit.iter().fold(0, |n, &bit| n << 1 | bit as u32) << (4 - digit.len());
+            char::from_digit(digit, 16).unwrap()
+        })
+        .collect::<String>();
+    assert_eq!(
+        day16::parse(&hex).unwrap_err().message,
+        "bit 18000: packets nested more than 1000 deep"
+    );
+}
+
+#[test]
+fn evaluation_errors() {
+    let comparison = operator(0, 5, vec![literal(0, 1), literal(0, 2), literal(0, 3)]);
+    assert_eq!(
+        comparison.evaluate(),
+        Err(PacketError::OperandCount {
+            type_id: 5,
+            count: 3
+        })
+    );
+    assert_eq!(
+        day16::value(&comparison),
+        Err(PacketError::OperandCount {
+            type_id: 5,
+            count: 3
+        })
+    );
+    assert_eq!(
+        operator(0, 2, vec![]).evaluate().unwrap_err().to_string(),
+        "operator with type ID 2 cannot take 0 sub-packets"
+    );
+    assert_eq!(
+        operator(0, 4, vec![literal(0, 1)]).evaluate(),
+        Err(PacketError::InvalidTypeId { type_id: 4 })
+    );
+
+    // Errors in sub-packets surface from the outermost packet.
+    let nested = operator(0, 0, vec![literal(0, 1), operator(0, 7, vec![])]);
+    assert_eq!(
+        nested.evaluate(),
+        Err(PacketError::OperandCount {
+            type_id: 7,
+            count: 0
+        })
+    );
+    assert_eq!(
+        operator(0, 0, vec![literal(0, 1), literal(0, 2)]).evaluate(),
+        Ok(3)
+    );
+}
+
+#[test]
+fn bit_reader() {
+    let mut reader = BitReader::from_hex("D2FE28F").unwrap();
+    assert_eq!(reader.len(), 28);
+    assert_eq!(reader.read(3), Ok(6));
+    assert_eq!(reader.read(3), Ok(4));
+    assert_eq!(reader.read(15), Ok(0b10111_11110_00101));
+    assert_eq!(reader.position(), 21);
+    assert_eq!(reader.read(7), Ok(0b000_1111));
+    assert_eq!(
+        reader.read(1),
+        Err(PacketError::UnexpectedEnd { offset: 28 })
+    );
+    assert!(BitReader::from_hex("D2FE2G").is_none());
+
+    let bytes = 0x0123_4567_89AB_CDEF_u64.to_be_bytes();
+    let mut reader = BitReader::new([[0xF0].as_slice(), &bytes].concat());
+    assert_eq!(reader.read(4), Ok(0xF));
+    assert_eq!(reader.read(64), Ok(0x0012_3456_789A_BCDE));
+    assert_eq!(reader.read(4), Ok(0xF));
+    assert_eq!(reader.read(0), Ok(0));
+}
+
+#[test]
+fn parses_raw_binary() {
+    for seed in 0..20 {
+        let packet = day16::generate_packet(&mut Rng::new(seed), 4);
+        let hex = packet.to_hex(|_| LengthType::Count).unwrap();
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        let mut parser = Parser::new(BitReader::new(bytes));
+        assert_eq!(parser.parse_packet(), Ok(packet), "seed {}", seed);
+    }
+}
+
+fn big(hex: &str) -> BigUint {
+    let mut n = BigUint::default();
+    for c in hex.chars() {
+        n.push_nibble(c.to_digit(16).unwrap() as u8);
+    }
+    n
+}
+
+#[test]
+fn big_arithmetic() {
+    let mut rng = Rng::new(16);
+    for _ in 0..1000 {
+        let a = rng.below(usize::MAX) as u128 >> rng.below(64);
+        let b = rng.below(usize::MAX) as u128 >> rng.below(64);
+        let (x, y) = (BigUint::from(a), BigUint::from(b));
+        assert_eq!((&x + &y).to_u128(), Some(a + b));
+        assert_eq!((&x * &y).to_u128(), Some(a * b));
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        assert_eq!(x.to_string(), a.to_string());
+        assert_eq!(x.bits() as u32, u128::BITS - a.leading_zeros());
+    }
+
+    assert_eq!(big("1").to_string(), "1");
+    let n = big(&format!("1{}", "0".repeat(25)));
+    assert_eq!(n.to_string(), "1267650600228229401496703205376");
+    assert_eq!(n.bits(), 101);
+    assert_eq!(n.to_u128(), Some(1 << 100));
+    assert_eq!(n.to_usize(), None);
+    assert_eq!((&n * &n).to_u128(), None);
+    assert_eq!(BigUint::default().to_string(), "0");
+    assert_eq!(big("000").bits(), 0);
+    assert_eq!(
+        (&BigUint::from(u128::MAX) * &BigUint::from(u128::MAX)).to_string(),
+        "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+    );
+}
+
+#[test]
+fn big_literals() {
+    // 2^100, far longer than a usize.
+    let packet = literal(3, 0);
+    let packet = Packet {
+        val: Value::Literal(big(&format!("1{}", "0".repeat(25)))),
+        ..packet
+    };
+    let hex = packet.to_hex(|_| LengthType::Bits).unwrap();
+    let parsed = day16::parse(&hex).unwrap();
+    assert_eq!(parsed, packet);
+    assert_eq!(
+        parsed.evaluate(),
+        Err(PacketError::Overflow { path: vec![] })
+    );
+    assert_eq!(
+        parsed.evaluate_big().unwrap().to_string(),
+        "1267650600228229401496703205376"
+    );
+}
+
+#[test]
+fn overflow_paths() {
+    let packet = operator(
+        0,
+        0,
+        vec![
+            literal(0, 1),
+            operator(0, 1, vec![literal(0, 1 << 40), literal(0, 1 << 40)]),
+        ],
+    );
+    let error = packet.evaluate().unwrap_err();
+    assert_eq!(error, PacketError::Overflow { path: vec![1] });
+    assert_eq!(error.to_string(), "value of sub-packet 1 overflows 64 bits");
+    assert_eq!(
+        packet.evaluate_big().unwrap().to_string(),
+        "1208925819614629174706177"
+    );
+
+    // Comparisons of huge values fit again.
+    let packet = operator(0, 5, vec![packet, literal(0, 2)]);
+    assert_eq!(
+        packet.evaluate(),
+        Err(PacketError::Overflow { path: vec![0, 1] })
+    );
+    assert_eq!(packet.evaluate_big(), Ok(BigUint::from(1usize)));
+    assert_eq!(day16::value(&packet).unwrap().to_usize(), Some(1));
+}
+
+#[test]
+fn printers() {
+    let packet = operator(
+        0,
+        1,
+        vec![
+            operator(1, 0, vec![literal(2, 1), literal(3, 3)]),
+            operator(4, 2, vec![literal(5, 7), literal(6, 9)]),
+        ],
+    );
+    assert_eq!(day16::infix(&packet), "(1 + 3) * min(7, 9)");
+    assert_eq!(day16::sexpr(&packet), "(* (+ 1 3) (min 7 9))");
+    assert_eq!(
+        day16::tree(&packet, &[]),
+        "version 0, type 1 (*)\n  \
+           version 1, type 0 (+)\n    \
+             version 2, type 4, literal 1\n    \
+             version 3, type 4, literal 3\n  \
+           version 4, type 2 (min)\n    \
+             version 5, type 4, literal 7\n    \
+             version 6, type 4, literal 9\n"
+    );
+
+    // Equal precedence keeps its parentheses, showing the tree's shape.
+    let packet = operator(
+        0,
+        0,
+        vec![
+            operator(0, 0, vec![literal(0, 1), literal(0, 2)]),
+            operator(0, 0, vec![literal(0, 3)]),
+            operator(0, 5, vec![literal(0, 4), literal(0, 5)]),
+            operator(0, 7, vec![]),
+        ],
+    );
+    assert_eq!(day16::infix(&packet), "(1 + 2) + sum(3) + (4 > 5) + eq()");
+    assert_eq!(day16::sexpr(&packet), "(+ (+ 1 2) (+ 3) (> 4 5) (=))");
+}
+
+#[test]
+fn tree_spans() {
+    let (packet, spans) = day16::parse_with_spans("9C0141080250320F1802104A08").unwrap();
+    assert_eq!(day16::infix(&packet), "1 + 3 == 2 * 2");
+    assert_eq!(
+        day16::tree(&packet, &spans),
+        "[0..102] version 4, type 7 (=), length type 0 (80 bits)\n  \
+           [22..62] version 2, type 0 (+), length type 1 (2 sub-packets)\n    \
+             [40..51] version 2, type 4, literal 1\n    \
+             [51..62] version 4, type 4, literal 3\n  \
+           [62..102] version 6, type 1 (*), length type 1 (2 sub-packets)\n    \
+             [80..91] version 0, type 4, literal 2\n    \
+             [91..102] version 2, type 4, literal 2\n"
+    );
+
+    // Spans of encoder output match what the encoder chose.
+    for seed in 0..20 {
+        let packet = day16::generate_packet(&mut Rng::new(seed), 4);
+        let bits = packet.to_bits(|_| LengthType::Count).unwrap();
+        let mut parser = Parser::new(BitReader::from_bits(&bits));
+        parser.parse_packet().unwrap();
+        assert_eq!(parser.spans()[0].bits, 0..bits.len(), "seed {}", seed);
+        assert!(parser
+            .spans()
+            .iter()
+            .all(|span| matches!(span.length, None | Some((LengthType::Count, _)))));
+    }
+}
@@ -0,0 +1,43 @@
+//! Helpers for the tests that run the `aoc` binary.
+
+// Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::env;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+/// Runs `aoc` with `args` from the repository root, where the inputs are.
+pub fn aoc(args: &[&str]) -> Output {
+    aoc_with_stdin(args, "")
+}
+
+/// Like `aoc`, with `stdin` as its standard input.
+pub fn aoc_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(args)
+        .current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join(".."))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// A path in the temporary directory for `name`, distinct for each test
+/// process so that concurrent runs do not clash.
+pub fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("aoc-{}-{}", name, process::id()))
+}
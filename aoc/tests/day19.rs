@@ -0,0 +1,158 @@
+use common::Rng;
+use day19::Fingerprint;
+use day19::Rotation;
+use std::collections::HashSet;
+
+#[test]
+fn exactly_24_proper_rotations() {
+    let rotations = Rotation::all();
+    assert_eq!(rotations.len(), 24);
+    assert_eq!(rotations[0], Rotation::IDENTITY);
+
+    let distinct: HashSet<Rotation> = rotations.iter().copied().collect();
+    assert_eq!(distinct.len(), 24);
+
+    for rotation in &rotations {
+        assert_eq!(rotation.determinant(), 1, "{:?}", rotation);
+        for row in rotation.matrix() {
+            // Each row and column holds a single ±1, so no axis is dropped
+            // or duplicated.
+            assert_eq!(
+                row.iter().map(|v| v.abs()).sum::<isize>(),
+                1,
+                "{:?}",
+                rotation
+            );
+        }
+        assert_eq!(rotation.then(&rotation.inverse()), Rotation::IDENTITY);
+        assert_eq!(rotation.inverse().then(rotation), Rotation::IDENTITY);
+    }
+}
+
+#[test]
+fn closed_under_composition() {
+    let rotations = Rotation::all();
+    for a in &rotations {
+        for b in &rotations {
+            assert!(rotations.contains(&a.then(b)), "{:?} then {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn apply() {
+    let point = (1, 2, 3);
+    let rotations = Rotation::all();
+    let images: HashSet<_> = rotations.iter().map(|r| r.apply(point)).collect();
+    assert_eq!(images.len(), 24);
+
+    for a in &rotations {
+        assert_eq!(a.inverse().apply(a.apply(point)), point);
+        for b in &rotations {
+            assert_eq!(a.then(b).apply(point), b.apply(a.apply(point)));
+        }
+    }
+}
+
+#[test]
+fn fingerprint_ignores_orientation() {
+    let (input, _, _) = day19::generate_scan(&mut Rng::new(1), 2);
+    let reports = day19::parse(&input).unwrap();
+    let fingerprint = Fingerprint::new(&reports[1]);
+
+    for rotation in Rotation::all() {
+        let rotated = reports[1].iter().map(|&b| rotation.apply(b)).collect();
+        let n = reports[1].len();
+        assert_eq!(
+            Fingerprint::new(&rotated).shared(&fingerprint),
+            n * (n - 1) / 2
+        );
+    }
+    assert!(Fingerprint::new(&reports[0]).shared(&fingerprint) >= 12 * 11 / 2);
+}
+
+#[test]
+fn fingerprint_align() {
+    let (input, _, scanners) = day19::generate_scan(&mut Rng::new(2), 2);
+    let reports = day19::parse(&input).unwrap();
+    let rotations = Rotation::all();
+    let (fingerprint0, fingerprint1) =
+        (Fingerprint::new(&reports[0]), Fingerprint::new(&reports[1]));
+
+    let (delta, rotation) = fingerprint0.align(&fingerprint1, &rotations, 12).unwrap();
+    assert_eq!(delta, scanners[1]);
+    let lined_up = reports[1]
+        .iter()
+        .map(|&b| rotation.apply(b))
+        .filter(|b| reports[0].contains(&(b.0 + delta.0, b.1 + delta.1, b.2 + delta.2)))
+        .count();
+    assert!(lined_up >= 12);
+
+    let moved: HashSet<_> = reports[1].iter().map(|b| (b.0 + 5000, b.1, b.2)).collect();
+    assert_eq!(
+        fingerprint1.align(&Fingerprint::new(&moved), &rotations, 12),
+        Some(((-5000, 0, 0), Rotation::IDENTITY))
+    );
+
+    let unrelated = HashSet::from([(0, 0, 0), (1, 2, 3)]);
+    assert_eq!(
+        fingerprint0.align(&Fingerprint::new(&unrelated), &rotations, 12),
+        None
+    );
+}
+
+#[test]
+fn many_scanners() {
+    let (input, beacons, scanners) = day19::generate_scan(&mut Rng::new(0), 40);
+    let reports = day19::parse(&input).unwrap();
+    assert_eq!(day19::align(&reports), Ok((beacons, scanners)));
+}
+
+#[test]
+fn disconnected_clusters() {
+    let (input1, beacons1, scanners1) = day19::generate_scan(&mut Rng::new(3), 3);
+    let (input2, beacons2, scanners2) = day19::generate_scan(&mut Rng::new(4), 2);
+    let input2 = input2
+        .replace("--- scanner 1 ---", "--- scanner 4 ---")
+        .replace("--- scanner 0 ---", "--- scanner 3 ---");
+    let reports = day19::parse(&format!("{}\n{}", input1, input2)).unwrap();
+
+    let clusters = day19::clusters(&reports, 12);
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(day19::part1(&reports), Err(clusters.clone()));
+
+    for (cluster, (origin, beacons, scanners)) in clusters
+        .iter()
+        .zip([(0, beacons1, scanners1), (3, beacons2, scanners2)])
+    {
+        assert_eq!(cluster.origin, origin);
+        assert_eq!(cluster.beacons, beacons);
+
+        let placed: Vec<_> = cluster
+            .scanners
+            .iter()
+            .map(|p| (p.scanner, p.position))
+            .collect();
+        let expected: Vec<_> = scanners
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (origin + i, p))
+            .collect();
+        assert_eq!(placed, expected);
+    }
+}
+
+#[test]
+fn placements_map_reports_onto_beacons() {
+    let (input, _, _) = day19::generate_scan(&mut Rng::new(5), 6);
+    let reports = day19::parse(&input).unwrap();
+    let clusters = day19::clusters(&reports, 12);
+
+    for placement in &clusters[0].scanners {
+        for &beacon in &reports[placement.scanner] {
+            let (x, y, z) = placement.rotation.apply(beacon);
+            let p = placement.position;
+            assert!(clusters[0].beacons.contains(&(x + p.0, y + p.1, z + p.2)));
+        }
+    }
+}
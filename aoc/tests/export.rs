@@ -0,0 +1,81 @@
+mod common;
+
+use common::aoc;
+use common::temp_path;
+use std::fs;
+
+#[test]
+fn day19_files() {
+    let dir = temp_path("export");
+    let output = aoc(&["export", "19", "--out", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    let (scanners, beacons) = (read("scanners.csv"), read("beacons.csv"));
+    let (json, ply) = (read("reconstruction.json"), read("cluster0.ply"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    let scanner_count = include_str!("../../day19/input")
+        .matches("--- scanner")
+        .count();
+    assert_eq!(scanners.lines().count(), scanner_count + 1);
+    assert!(scanners.starts_with("cluster,scanner,x,y,z,r11,"));
+    assert!(scanners.contains("\n0,0,0,0,0,1,0,0,0,1,0,0,0,1\n"));
+
+    assert_eq!(beacons.lines().count(), 394 + 1);
+    assert!(beacons.starts_with("cluster,x,y,z\n"));
+
+    assert!(json.starts_with("{\"clusters\":[{\"origin\":0,\"scanners\":[{\"scanner\":0,"));
+    assert_eq!(json.matches("\"position\"").count(), scanner_count);
+
+    assert!(ply.starts_with("ply\nformat ascii 1.0\n"));
+    assert!(ply.contains(&format!("element vertex {}\n", 394 + scanner_count)));
+    let vertices = ply.split("end_header\n").nth(1).unwrap();
+    assert_eq!(vertices.lines().count(), 394 + scanner_count);
+}
+
+#[test]
+fn errors() {
+    let output = aoc(&["export", "3", "--out", "unused"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("day 3 has nothing to export"));
+
+    let output = aoc(&["export", "19"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("missing --out <dir>"));
+}
+
+#[test]
+fn day24_files() {
+    let dir = temp_path("export-24");
+    let output = aoc(&["export", "24", "--out", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    let (program, decompiled) = (read("program.txt"), read("decompiled.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(program, include_str!("../../day24/input"));
+    assert_eq!(decompiled.matches("block ").count(), 14);
+}
+
+#[test]
+fn day16_files() {
+    let dir = temp_path("export-16");
+    let output = aoc(&["export", "16", "--out", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    let (infix, sexpr, tree) = (read("infix.txt"), read("sexpr.txt"), read("tree.txt"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    let (packet, spans) = day16::parse_with_spans(include_str!("../../day16/input")).unwrap();
+    assert_eq!(infix, day16::infix(&packet) + "\n");
+    assert_eq!(sexpr, day16::sexpr(&packet) + "\n");
+    assert_eq!(tree.lines().count(), spans.len());
+    assert!(tree.starts_with("[0.."));
+}
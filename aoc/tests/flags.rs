@@ -0,0 +1,107 @@
+mod common;
+
+use common::aoc;
+use std::process::Output;
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn stderr(output: Output) -> String {
+    assert!(!output.status.success(), "{:?}", output);
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn pair_flag() {
+    let out = stdout(aoc(&[
+        "run",
+        "6",
+        "--input",
+        "day06/test_input",
+        "--days",
+        "18,80",
+    ]));
+    assert!(out.contains("part 1: 26 ("));
+    assert!(out.contains("part 2: 5934 ("));
+}
+
+#[test]
+fn single_value_applies_to_both_parts() {
+    let out = stdout(aoc(&[
+        "run",
+        "14",
+        "--input",
+        "day14/test_input",
+        "--steps",
+        "10",
+    ]));
+    assert!(out.contains("part 1: 1588 ("));
+    assert!(out.contains("part 2: 1588 ("));
+}
+
+#[test]
+fn optional_flag_replaces_input() {
+    let out = stdout(aoc(&["run", "17", "--target", "x=20..30,y=-10..-5"]));
+    assert!(out.contains("part 1: 45 ("));
+    assert!(out.contains("part 2: 112 ("));
+}
+
+#[test]
+fn deep_target() {
+    let out = stdout(aoc(&["run", "17", "--target", "x=20..30,y=-1000..-995"]));
+    assert!(out.contains("part 1: 499500 ("));
+
+    let err = stderr(aoc(&["run", "17", "--target", "x=20..30,y=-5..5"]));
+    assert!(err.contains("target must be below the probe (found `y=-5..5`)"));
+}
+
+#[test]
+fn invalid_value() {
+    let err = stderr(aoc(&["run", "22", "--region", "big"]));
+    assert!(err.contains("day 22: invalid value `big` for --region"));
+}
+
+#[test]
+fn overflowing_values() {
+    let err = stderr(aoc(&["run", "6", "--days", "1000"]));
+    assert!(err.contains("invalid value `1000` for --days: too many lanternfish to count"));
+
+    let err = stderr(aoc(&["run", "14", "--steps", "100"]));
+    assert!(err.contains("invalid value `100` for --steps: the polymer grows too long"));
+
+    let err = stderr(aoc(&["run", "21", "--win-score", "1000,40"]));
+    assert!(err.contains("invalid value `1000,40` for --win-score: too many universes"));
+}
+
+#[test]
+fn infinitely_many_lit_pixels() {
+    let out = stdout(aoc(&["run", "20", "--steps", "1,4"]));
+    assert!(out.contains("part 1: no solution ("));
+    assert!(!out.contains("part 2: no solution ("));
+}
+
+#[test]
+fn too_small_overlap() {
+    for overlap in ["0", "1", "2"] {
+        let err = stderr(aoc(&["run", "19", "--min-overlap", overlap]));
+        assert!(err.contains("for --min-overlap: scanners must share at least 3 beacons"));
+    }
+}
+
+#[test]
+fn unknown_flag() {
+    let err = stderr(aoc(&["run", "6", "--steps", "3"]));
+    assert!(err.contains("day 6 takes no --steps flag"));
+    assert!(err.contains("--days (default 80,256)"));
+
+    let err = stderr(aoc(&["run", "all", "--nope", "3"]));
+    assert!(err.contains("no day takes a --nope flag"));
+}
+
+#[test]
+fn clusters_instead_of_panicking() {
+    let out = stdout(aoc(&["run", "19", "--min-overlap", "14"]));
+    assert!(out.contains("cluster at scanner 0 (scanners 0): 26\n"));
+}
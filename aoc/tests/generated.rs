@@ -0,0 +1,171 @@
+mod common;
+
+use ::common::Rng;
+use common::aoc;
+use day24::alu::accepts;
+use std::collections::HashSet;
+use std::iter;
+
+const SEEDS: u64 = 20;
+
+macro_rules! parses {
+    ($($name:ident),+ $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                for size in [1, 4, 10] {
+                    for seed in 0..SEEDS {
+                        let input = $name::generate(&mut Rng::new(seed), size);
+                        if let Err(e) = $name::parse(&input) {
+                            panic!("seed {}, size {}: {}\n{}", seed, size, e, input);
+                        }
+                    }
+                }
+            }
+        )+
+    };
+}
+
+parses!(
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day16, day17, day19, day20, day21, day22, day23, day24, day25,
+);
+
+#[test]
+fn generate_is_deterministic() {
+    let generate = |seed: &str| {
+        let output = aoc(&["generate", "12", "--seed", seed, "--size", "6"]);
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(generate("7"), generate("7"));
+    assert_ne!(generate("7"), generate("8"));
+}
+
+#[test]
+fn day06_matches_simulation() {
+    for seed in 0..SEEDS {
+        let input = day06::generate(&mut Rng::new(seed), 10);
+        let mut fishes: Vec<usize> = input
+            .trim()
+            .split(',')
+            .map(|t| t.parse().unwrap())
+            .collect();
+        let timers = day06::parse(&input).unwrap();
+
+        for day in 1..=40 {
+            let spawned = fishes.iter().filter(|&&t| t == 0).count();
+            for t in fishes.iter_mut() {
+                *t = if *t == 0 { 6 } else { *t - 1 };
+            }
+            fishes.extend(iter::repeat_n(8, spawned));
+            assert_eq!(
+                day06::fishes_after(&timers, day),
+                Some(fishes.len()),
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn day07_matches_brute_force() {
+    for seed in 0..SEEDS {
+        let positions = day07::parse(&day07::generate(&mut Rng::new(seed), 10)).unwrap();
+        let max = *positions.iter().max().unwrap();
+        let fuel = |cost: fn(u32) -> u32| {
+            (0..=max)
+                .map(|to| positions.iter().map(|&p| cost(p.abs_diff(to))).sum::<u32>())
+                .min()
+                .unwrap()
+        };
+
+        assert_eq!(day07::part1(&positions), fuel(|d| d), "seed {}", seed);
+        assert_eq!(
+            day07::part2(&positions),
+            fuel(|d| d * (d + 1) / 2),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn day11_large_grids_synchronize() {
+    for seed in 0..3 {
+        let grid = day11::parse(&day11::generate(&mut Rng::new(seed), 50)).unwrap();
+        assert_eq!(grid.width(), 50, "seed {}", seed);
+        day11::part2(&grid);
+    }
+}
+
+#[test]
+fn day22_matches_brute_force() {
+    for seed in 0..SEEDS {
+        let input = day22::generate(&mut Rng::new(seed), 10);
+        let steps = day22::parse(&input).unwrap();
+        let mut on = HashSet::new();
+
+        for line in input.lines() {
+            let (state, ranges) = line.split_once(' ').unwrap();
+            let bounds: Vec<(i64, i64)> = ranges
+                .split(',')
+                .map(|range| {
+                    let (low, high) = range[2..].split_once("..").unwrap();
+                    (
+                        low.parse::<i64>().unwrap().max(-20),
+                        high.parse::<i64>().unwrap().min(20),
+                    )
+                })
+                .collect();
+
+            for x in bounds[0].0..=bounds[0].1 {
+                for y in bounds[1].0..=bounds[1].1 {
+                    for z in bounds[2].0..=bounds[2].1 {
+                        if state == "on" {
+                            on.insert((x, y, z));
+                        } else {
+                            on.remove(&(x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(
+            day22::cubes_within(&steps, 20),
+            on.len() as isize,
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn day24_answers_are_accepted() {
+    for seed in 0..SEEDS {
+        let program = day24::parse(&day24::generate(&mut Rng::new(seed), 0)).unwrap();
+        let (largest, smallest) = (day24::part1(&program), day24::part2(&program));
+
+        assert!(smallest <= largest, "seed {}", seed);
+        assert_eq!(accepts(&program, largest), Ok(true), "seed {}", seed);
+        assert_eq!(accepts(&program, smallest), Ok(true), "seed {}", seed);
+    }
+}
+
+#[test]
+fn day19_matches_generated_scan() {
+    for seed in 0..SEEDS {
+        let (input, beacons, scanners) = day19::generate_scan(&mut Rng::new(seed), 5);
+        let reports = day19::parse(&input).unwrap();
+
+        assert_eq!(
+            day19::align(&reports),
+            Ok((beacons, scanners)),
+            "seed {}",
+            seed
+        );
+    }
+}
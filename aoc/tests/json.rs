@@ -0,0 +1,35 @@
+mod common;
+
+use common::aoc;
+
+fn aoc_json(args: &[&str]) -> String {
+    let output = aoc(&[&["run"], args, &["--format", "json"]].concat());
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn integers() {
+    let out = aoc_json(&["6", "--input", "day06/test_input"]);
+    assert!(out.starts_with("{\"day\":6,\"part1\":5934,\"part2\":26984457539,\"elapsed_ms\":"));
+    assert!(out.ends_with("}\n"));
+}
+
+#[test]
+fn rendered_grid() {
+    let out = aoc_json(&["13", "--input", "day13/test_input"]);
+    assert!(out.starts_with(concat!(
+        "{\"day\":13,\"part1\":17,\"part2\":[",
+        "\"#####\",\"#...#\",\"#...#\",\"#...#\",\"#####\",\".....\",\".....\"",
+        "],\"elapsed_ms\":"
+    )));
+}
+
+#[test]
+fn single_part_and_no_solution() {
+    let out = aoc_json(&["25", "--input", "day25/test_input1"]);
+    assert!(out.starts_with("{\"day\":25,\"part1\":58,\"elapsed_ms\":"));
+
+    let out = aoc_json(&["23", "--unfold", "DDD,CBB,BAA,ACC"]);
+    assert!(out.contains("\"part2\":null,"));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use ::common::ParseError;
+use common::aoc;
+use common::temp_path;
+use std::fs;
+
+fn error(day: u32, line: usize, column: usize, text: &str, message: &str) -> ParseError {
+    ParseError {
+        day,
+        line,
+        column,
+        text: text.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn invalid_command() {
+    assert_eq!(
+        day02::parse("forward 5\nsideways 3\n").err(),
+        Some(error(2, 2, 1, "sideways", "invalid command"))
+    );
+}
+
+#[test]
+fn short_board_row() {
+    let input =
+        "7,4,9\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16\n 6 10  3 18  5\n 1 12 20 15 19\n";
+    assert_eq!(
+        day04::parse(input).err(),
+        Some(error(
+            4,
+            5,
+            1,
+            "21  9 14 16",
+            "expected 5 numbers per board row"
+        ))
+    );
+}
+
+#[test]
+fn nested_positions_are_relative_to_the_input() {
+    assert_eq!(
+        day05::parse("0,9 -> 5,9\n8,0 => 0,8\n").err(),
+        Some(error(5, 2, 1, "8,0 => 0,8", "expected `x1,y1 -> x2,y2`"))
+    );
+    assert_eq!(
+        day05::parse("0,9 -> 5,9\n0,9 -> 5,x9\n").err(),
+        Some(error(
+            5,
+            2,
+            10,
+            "x9",
+            "invalid y coordinate: invalid digit found in string"
+        ))
+    );
+    assert_eq!(
+        day22::parse("on x=10..12,y=10..12,z=10..12\noff x=9..11,q=9..11,z=9..11\n").err(),
+        Some(error(22, 2, 13, "q=9..11", "expected `y=` range"))
+    );
+}
+
+#[test]
+fn invalid_character() {
+    assert_eq!(
+        day10::parse("[(]\n{a}\n").err(),
+        Some(error(10, 2, 2, "a", "invalid character"))
+    );
+    assert_eq!(
+        day10::parse("[]]\n").err(),
+        Some(error(10, 1, 3, "]", "unexpected extra closing character"))
+    );
+}
+
+#[test]
+fn unexpected_end_of_input() {
+    assert_eq!(
+        day24::parse("inp w\nadd x\n").err(),
+        Some(error(24, 2, 6, "", "missing arguments"))
+    );
+    assert_eq!(
+        day21::parse("Player 1 starting position: 4\n").err(),
+        Some(error(21, 2, 1, "", "expected player starting position"))
+    );
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        day24::parse("inp w\nmul x 2 3\n")
+            .err()
+            .unwrap()
+            .to_string(),
+        "day 24, line 2, column 9: too many arguments (found `3`)"
+    );
+    assert_eq!(
+        day24::parse("inp w\nadd x\n").err().unwrap().to_string(),
+        "day 24, line 2, column 6: missing arguments (found end of input)"
+    );
+}
+
+#[test]
+fn runner_reports_parse_errors() {
+    let path = temp_path("parse-error");
+    fs::write(&path, "forward 5\nsideways 3\n").unwrap();
+
+    let output = aoc(&["run", "2", "--input", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("day 2, line 2, column 1: invalid command (found `sideways`)"));
+}
+
+#[test]
+fn runner_reports_packet_errors() {
+    let path = temp_path("packet-error");
+    // A `>` operator with a single operand.
+    fs::write(&path, "16004408\n").unwrap();
+
+    let output = aoc(&["run", "16", "--input", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("operator with type ID 5 cannot take 1 sub-packets"));
+    assert!(!stderr.contains("panicked"));
+}
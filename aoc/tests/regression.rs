@@ -0,0 +1,409 @@
+macro_rules! input {
+    ($day:literal, $file:literal) => {
+        include_str!(concat!("../../", $day, "/", $file))
+    };
+}
+
+#[test]
+fn day01() {
+    let input = day01::parse(input!("day01", "input")).unwrap();
+    assert_eq!(day01::part1(&input), 1466);
+    assert_eq!(day01::part2(&input), 1491);
+}
+
+#[test]
+fn day01_example() {
+    let input = day01::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n").unwrap();
+    assert_eq!(day01::part1(&input), 7);
+    assert_eq!(day01::part2(&input), 5);
+}
+
+#[test]
+fn day02() {
+    let input = day02::parse(input!("day02", "input")).unwrap();
+    assert_eq!(day02::part1(&input), Some(1762050));
+    assert_eq!(day02::part2(&input), Some(1855892637));
+}
+
+#[test]
+fn day02_example() {
+    let input = day02::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n").unwrap();
+    assert_eq!(day02::part1(&input), Some(150));
+    assert_eq!(day02::part2(&input), Some(900));
+}
+
+#[test]
+fn day03() {
+    let input = day03::parse(input!("day03", "input")).unwrap();
+    assert_eq!(day03::part1(&input), 4147524);
+    assert_eq!(day03::part2(&input), 3570354);
+}
+
+#[test]
+fn day03_example() {
+    let input = day03::parse(input!("day03", "test_input")).unwrap();
+    assert_eq!(day03::part1(&input), 198);
+    assert_eq!(day03::part2(&input), 230);
+}
+
+#[test]
+fn day04() {
+    let input = day04::parse(input!("day04", "input")).unwrap();
+    assert_eq!(day04::part1(&input), Some(58412));
+    assert_eq!(day04::part2(&input), Some(10030));
+}
+
+#[test]
+fn day04_example() {
+    let input = day04::parse(input!("day04", "test_input")).unwrap();
+    assert_eq!(day04::part1(&input), Some(4512));
+    assert_eq!(day04::part2(&input), Some(1924));
+}
+
+#[test]
+fn day04_no_winner() {
+    let board = "1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25\n";
+    let input = day04::parse(&format!("1,7,13,19\n\n{}", board)).unwrap();
+    assert_eq!(day04::part1(&input), None);
+    assert_eq!(day04::part2(&input), None);
+}
+
+#[test]
+fn day05() {
+    let input = day05::parse(input!("day05", "input")).unwrap();
+    assert_eq!(day05::part1(&input), 6311);
+    assert_eq!(day05::part2(&input), 19929);
+}
+
+#[test]
+fn day05_example() {
+    let input = day05::parse(input!("day05", "test_input")).unwrap();
+    assert_eq!(day05::part1(&input), 5);
+    assert_eq!(day05::part2(&input), 12);
+}
+
+#[test]
+fn day06() {
+    let input = day06::parse(input!("day06", "input")).unwrap();
+    assert_eq!(day06::part1(&input), Some(360610));
+    assert_eq!(day06::part2(&input), Some(1631629590423));
+}
+
+#[test]
+fn day06_example() {
+    let input = day06::parse(input!("day06", "test_input")).unwrap();
+    assert_eq!(day06::part1(&input), Some(5934));
+    assert_eq!(day06::part2(&input), Some(26984457539));
+}
+
+#[test]
+fn day07() {
+    let input = day07::parse(input!("day07", "input")).unwrap();
+    assert_eq!(day07::part1(&input), 343605);
+    assert_eq!(day07::part2(&input), 96744904);
+}
+
+#[test]
+fn day07_example() {
+    let input = day07::parse(input!("day07", "test_input")).unwrap();
+    assert_eq!(day07::part1(&input), 37);
+    assert_eq!(day07::part2(&input), 168);
+}
+
+#[test]
+fn day08() {
+    let input = day08::parse(input!("day08", "input")).unwrap();
+    assert_eq!(day08::part1(&input), 330);
+    assert_eq!(day08::part2(&input), Some(1010472));
+}
+
+#[test]
+fn day08_example() {
+    let input = day08::parse(input!("day08", "test_input")).unwrap();
+    assert_eq!(day08::part1(&input), 26);
+    assert_eq!(day08::part2(&input), Some(61229));
+
+    let input = day08::parse(input!("day08", "test_input1")).unwrap();
+    assert_eq!(day08::part2(&input), Some(5353));
+}
+
+#[test]
+fn day09() {
+    let input = day09::parse(input!("day09", "input")).unwrap();
+    assert_eq!(day09::part1(&input), 539);
+    assert_eq!(day09::part2(&input), 736920);
+}
+
+#[test]
+fn day09_example() {
+    let input = day09::parse(input!("day09", "test_input")).unwrap();
+    assert_eq!(day09::part1(&input), 15);
+    assert_eq!(day09::part2(&input), 1134);
+}
+
+#[test]
+fn day10() {
+    let input = day10::parse(input!("day10", "input")).unwrap();
+    assert_eq!(day10::part1(&input), 296535);
+    assert_eq!(day10::part2(&input), Some(4245130838));
+}
+
+#[test]
+fn day10_example() {
+    let input = day10::parse(input!("day10", "test_input")).unwrap();
+    assert_eq!(day10::part1(&input), 26397);
+    assert_eq!(day10::part2(&input), Some(288957));
+}
+
+#[test]
+fn day10_even_incomplete_lines() {
+    let input = day10::parse("[(\n<\n").unwrap();
+    assert_eq!(day10::part2(&input), None);
+}
+
+#[test]
+fn day11() {
+    let input = day11::parse(input!("day11", "input")).unwrap();
+    assert_eq!(day11::part1(&input), 1669);
+    assert_eq!(day11::part2(&input), 351);
+}
+
+#[test]
+fn day11_example() {
+    let input = day11::parse(input!("day11", "test_input")).unwrap();
+    assert_eq!(day11::part1(&input), 1656);
+    assert_eq!(day11::part2(&input), 195);
+}
+
+#[test]
+fn day12() {
+    let input = day12::parse(input!("day12", "input")).unwrap();
+    assert_eq!(day12::part1(&input), 5457);
+    assert_eq!(day12::part2(&input), 128506);
+}
+
+#[test]
+fn day12_example() {
+    for (file, part1, part2) in [
+        (input!("day12", "test_input1"), 10, 36),
+        (input!("day12", "test_input2"), 19, 103),
+        (input!("day12", "test_input3"), 226, 3509),
+    ] {
+        let input = day12::parse(file).unwrap();
+        assert_eq!(day12::part1(&input), part1);
+        assert_eq!(day12::part2(&input), part2);
+    }
+}
+
+#[test]
+fn day13() {
+    let input = day13::parse(input!("day13", "input")).unwrap();
+    assert_eq!(day13::part1(&input), 684);
+    assert_eq!(
+        day13::part2(&input).to_string(),
+        "\
+..##.###..####.###..#.....##..#..#.#..#.
+...#.#..#....#.#..#.#....#..#.#.#..#..#.
+...#.#..#...#..###..#....#....##...####.
+...#.###...#...#..#.#....#.##.#.#..#..#.
+#..#.#.#..#....#..#.#....#..#.#.#..#..#.
+.##..#..#.####.###..####..###.#..#.#..#."
+    );
+}
+
+#[test]
+fn day13_example() {
+    let input = day13::parse(input!("day13", "test_input")).unwrap();
+    assert_eq!(day13::part1(&input), 17);
+    assert_eq!(
+        day13::part2(&input).to_string(),
+        "\
+#####
+#...#
+#...#
+#...#
+#####
+.....
+....."
+    );
+}
+
+#[test]
+fn day14() {
+    let input = day14::parse(input!("day14", "input")).unwrap();
+    assert_eq!(day14::part1(&input), Some(2947));
+    assert_eq!(day14::part2(&input), Some(3232426226464));
+}
+
+#[test]
+fn day14_example() {
+    let input = day14::parse(input!("day14", "test_input")).unwrap();
+    assert_eq!(day14::part1(&input), Some(1588));
+    assert_eq!(day14::part2(&input), Some(2188189693529));
+}
+
+#[test]
+fn day15() {
+    let input = day15::parse(input!("day15", "input")).unwrap();
+    assert_eq!(day15::part1(&input), 685);
+    assert_eq!(day15::part2(&input), 2995);
+}
+
+#[test]
+fn day15_example() {
+    let input = day15::parse(input!("day15", "test_input")).unwrap();
+    assert_eq!(day15::part1(&input), 40);
+    assert_eq!(day15::part2(&input), 315);
+}
+
+#[test]
+fn day16() {
+    let input = day16::parse(input!("day16", "input")).unwrap();
+    assert_eq!(day16::part1(&input), 1012);
+    assert_eq!(day16::part2(&input), Ok(2223947372407));
+}
+
+#[test]
+fn day16_example() {
+    for (transmission, version_sum) in [
+        ("8A004A801A8002F478", 16),
+        ("620080001611562C8802118E34", 12),
+        ("C0015000016115A2E0802F182340", 23),
+        ("A0016C880162017C3686B18A3D4780", 31),
+    ] {
+        assert_eq!(
+            day16::part1(&day16::parse(transmission).unwrap()),
+            version_sum
+        );
+    }
+
+    for (transmission, value) in [
+        ("C200B40A82", 3),
+        ("04005AC33890", 54),
+        ("880086C3E88112", 7),
+        ("CE00C43D881120", 9),
+        ("D8005AC2A8F0", 1),
+        ("F600BC2D8F", 0),
+        ("9C005AC2F8F0", 0),
+        ("9C0141080250320F1802104A08", 1),
+    ] {
+        assert_eq!(
+            day16::part2(&day16::parse(transmission).unwrap()),
+            Ok(value)
+        );
+    }
+}
+
+#[test]
+fn day17() {
+    let input = day17::parse(input!("day17", "input")).unwrap();
+    assert_eq!(day17::part1(&input), 5778);
+    assert_eq!(day17::part2(&input), 2576);
+}
+
+#[test]
+fn day17_example() {
+    let input = day17::parse("target area: x=20..30, y=-10..-5\n").unwrap();
+    assert_eq!(day17::part1(&input), 45);
+    assert_eq!(day17::part2(&input), 112);
+}
+
+#[test]
+fn day19() {
+    let input = day19::parse(input!("day19", "input")).unwrap();
+    assert_eq!(day19::part1(&input), Ok(394));
+    assert_eq!(day19::part2(&input), Ok(12304));
+}
+
+#[test]
+fn day19_example() {
+    let input = day19::parse(input!("day19", "test_input")).unwrap();
+    assert_eq!(day19::part1(&input), Ok(79));
+    assert_eq!(day19::part2(&input), Ok(3621));
+}
+
+#[test]
+fn day20() {
+    let input = day20::parse(input!("day20", "input")).unwrap();
+    assert_eq!(day20::part1(&input), Some(5275));
+    assert_eq!(day20::part2(&input), Some(16482));
+}
+
+#[test]
+fn day20_example() {
+    let input = day20::parse(input!("day20", "test_input")).unwrap();
+    assert_eq!(day20::part1(&input), Some(35));
+    assert_eq!(day20::part2(&input), Some(3351));
+}
+
+#[test]
+fn day21() {
+    let input = day21::parse(input!("day21", "input")).unwrap();
+    assert_eq!(day21::part1(&input), 920580);
+    assert_eq!(day21::part2(&input), Some(647920021341197));
+}
+
+#[test]
+fn day21_example() {
+    let input = day21::parse(input!("day21", "test_input")).unwrap();
+    assert_eq!(day21::part1(&input), 739785);
+    assert_eq!(day21::part2(&input), Some(444356092776315));
+}
+
+#[test]
+fn day22() {
+    let input = day22::parse(input!("day22", "input")).unwrap();
+    assert_eq!(day22::part1(&input), 543306);
+    assert_eq!(day22::part2(&input), 1285501151402480);
+}
+
+#[test]
+fn day22_example() {
+    let input = day22::parse(input!("day22", "test_input")).unwrap();
+    assert_eq!(day22::part1(&input), 39);
+
+    let input = day22::parse(input!("day22", "test_input1")).unwrap();
+    assert_eq!(day22::part1(&input), 590784);
+}
+
+#[test]
+fn day23() {
+    let input = day23::parse(input!("day23", "input")).unwrap();
+    assert_eq!(day23::part1(&input), Some(15358));
+    assert_eq!(day23::part2(&input), Some(51436));
+}
+
+#[test]
+fn day23_example() {
+    let input = day23::parse(
+        "\
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+",
+    )
+    .unwrap();
+    assert_eq!(day23::part1(&input), Some(12521));
+    assert_eq!(day23::part2(&input), Some(44169));
+}
+
+#[test]
+fn day24() {
+    let input = day24::parse(input!("day24", "input")).unwrap();
+    assert_eq!(day24::part1(&input), 92915979999498);
+    assert_eq!(day24::part2(&input), 21611513911181);
+}
+
+#[test]
+fn day25() {
+    let input = day25::parse(input!("day25", "input")).unwrap();
+    assert_eq!(day25::part1(&input), 386);
+}
+
+#[test]
+fn day25_example() {
+    let input = day25::parse(input!("day25", "test_input1")).unwrap();
+    assert_eq!(day25::part1(&input), 58);
+}
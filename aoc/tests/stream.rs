@@ -0,0 +1,92 @@
+mod common;
+
+use ::common::Rng;
+use common::aoc;
+use common::aoc_with_stdin;
+use std::process::Output;
+
+fn answers(output: Output) -> Vec<String> {
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.strip_prefix("  part "))
+        .map(|answer| answer.split(" (").next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn matches_run() {
+    for day in ["1", "2", "8", "10"] {
+        assert_eq!(
+            answers(aoc(&["stream", day])),
+            answers(aoc(&["run", day])),
+            "day {}",
+            day
+        );
+    }
+}
+
+#[test]
+fn partial_results() {
+    let depths = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+    let output = aoc_with_stdin(&["stream", "1", "--input", "-", "--every", "4"], depths);
+    assert!(output.status.success(), "{:?}", output);
+
+    let out = String::from_utf8(output.stdout).unwrap();
+    assert!(out.contains("after 4 lines: part 1: 3, part 2: 1\n"));
+    assert!(out.contains("after 8 lines: part 1: 6, part 2: 3\n"));
+    assert!(!out.contains("after 10 lines"));
+    assert!(out.ends_with("  part 1: 7\n  part 2: 5\n"));
+}
+
+#[test]
+fn errors() {
+    let output = aoc_with_stdin(
+        &["stream", "2", "--input", "-"],
+        "forward 5\r\ndown 5\r\nsideways 2\r\n",
+    );
+    assert!(!output.status.success());
+    let err = String::from_utf8(output.stderr).unwrap();
+    assert!(err.contains("-: day 2, line 3, column 1: invalid command"));
+
+    let output = aoc(&["stream", "3"]);
+    assert!(!output.status.success());
+    let err = String::from_utf8(output.stderr).unwrap();
+    assert!(err.contains("day 3 cannot be streamed"));
+}
+
+#[test]
+fn large_input() {
+    // Enough commands for the answers to overflow an `i32`.
+    let input = day02::generate(&mut Rng::new(0), 3_000_000);
+    let commands = day02::parse(&input).unwrap();
+    let (part1, part2) = (day02::part1(&commands), day02::part2(&commands));
+    assert!(part2 > Some(i32::MAX as i128));
+    let expected = [
+        format!("1: {}", part1.unwrap()),
+        format!("2: {}", part2.unwrap()),
+    ];
+
+    let output = aoc_with_stdin(&["stream", "2", "--input", "-"], &input);
+    assert_eq!(answers(output), expected);
+}
+
+#[test]
+fn running_middle_score() {
+    let input = day10::generate(&mut Rng::new(0), 500);
+    let mut scores = day10::Scores::default();
+    let mut completions = Vec::new();
+
+    for line in input.lines() {
+        let line = day10::check(line).unwrap();
+        if let day10::Line::Incomplete(completion) = &line {
+            completions.push(day10::completion_score(completion));
+        }
+        scores.push(&line);
+
+        completions.sort_unstable();
+        let expected = (completions.len() % 2 == 1).then(|| completions[completions.len() / 2]);
+        assert_eq!(scores.middle_score(), expected);
+    }
+}
@@ -0,0 +1,134 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error found while parsing a puzzle input, pointing at the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+/// Returns the 1-based line and column at which `text` starts within `source`.
+///
+/// `text` is expected to be a slice of `source`; anything else is reported at
+/// the start of `source`.
+fn position(source: &str, text: &str) -> (usize, usize) {
+    let start = source.as_ptr() as usize;
+    let offset = (text.as_ptr() as usize)
+        .checked_sub(start)
+        .filter(|&offset| offset <= source.len() && source.is_char_boundary(offset))
+        .unwrap_or(0);
+
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+impl ParseError {
+    pub fn new(day: u32, source: &str, text: &str, message: impl Into<String>) -> Self {
+        let (line, column) = position(source, text);
+        Self {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Re-anchors an error raised while parsing `part`, a slice of `source`, so
+    /// that its position is relative to `source`.
+    pub fn within(mut self, source: &str, part: &str) -> Self {
+        let (line, column) = position(source, part);
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+
+    /// Moves an error raised while parsing a single line to line `line` of
+    /// the input.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line += line - 1;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        if self.text.is_empty() {
+            write!(f, " (found end of input)")
+        } else {
+            write!(f, " (found `{}`)", self.text)
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// The text being parsed for a given day, used to build positioned errors.
+#[derive(Clone, Copy)]
+pub struct Source<'a> {
+    day: u32,
+    text: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(day: u32, text: &'a str) -> Self {
+        Self { day, text }
+    }
+
+    /// An empty slice at the very end of the source, for reporting missing input.
+    pub fn end(&self) -> &'a str {
+        &self.text[self.text.len()..]
+    }
+
+    pub fn error(&self, at: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.day, self.text, at, message)
+    }
+
+    pub fn parse<T>(&self, at: &str, what: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        at.parse()
+            .map_err(|e| self.error(at, format!("invalid {}: {}", what, e)))
+    }
+
+    /// Fails on the first character of `at` rejected by `valid`.
+    pub fn check_chars(
+        &self,
+        at: &str,
+        valid: impl Fn(char) -> bool,
+        message: &str,
+    ) -> Result<(), ParseError> {
+        match at.char_indices().find(|&(_, c)| !valid(c)) {
+            Some((i, c)) => Err(self.error(&at[i..i + c.len_utf8()], message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Parses a line-level value whose own errors are relative to `part`.
+    pub fn parse_part<T>(&self, part: &str) -> Result<T, ParseError>
+    where
+        T: FromStr<Err = ParseError>,
+    {
+        part.parse()
+            .map_err(|e: ParseError| e.within(self.text, part))
+    }
+}
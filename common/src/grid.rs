@@ -0,0 +1,334 @@
+use crate::ParseError;
+use crate::Source;
+use std::fmt;
+use std::ops::Index;
+use std::ops::IndexMut;
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const ALL: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+fn offset(
+    width: usize,
+    height: usize,
+    (x, y): (usize, usize),
+    (dx, dy): (isize, isize),
+) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx).filter(|&x| x < width)?;
+    let y = y.checked_add_signed(dy).filter(|&y| y < height)?;
+    Some((x, y))
+}
+
+fn wrap(width: usize, height: usize, x: isize, y: isize) -> (usize, usize) {
+    (
+        x.rem_euclid(width as isize) as usize,
+        y.rem_euclid(height as isize) as usize,
+    )
+}
+
+/// A rectangular grid stored row by row and indexed by `(x, y)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid of the given width from cells listed row by row.
+    ///
+    /// Panics if the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells do not fill rows of width {}",
+            cells.len(),
+            width
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parses a map with one cell per character, failing on characters
+    /// rejected by `cell` and on ragged or missing rows.
+    pub fn parse(
+        source: &Source,
+        text: &str,
+        cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = Vec::new();
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || width.is_some_and(|width| width != line.chars().count()) {
+                return Err(source.error(line, "rows must all have the same, non-zero length"));
+            }
+            width = Some(line.chars().count());
+
+            for (i, c) in line.char_indices() {
+                match cell(c) {
+                    Some(value) => cells.push(value),
+                    None => return Err(source.error(&line[i..i + c.len_utf8()], expected)),
+                }
+            }
+        }
+
+        match width {
+            Some(width) => Ok(Self::from_vec(width, cells)),
+            None => Err(source.error(&text[text.len()..], "empty grid")),
+        }
+    }
+
+    /// Parses a map of single decimal digits.
+    pub fn parse_digits(source: &Source, text: &str, expected: &str) -> Result<Self, ParseError>
+    where
+        T: From<u8>,
+    {
+        Self::parse(
+            source,
+            text,
+            |c| c.to_digit(10).map(|d| T::from(d as u8)),
+            expected,
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The position `(dx, dy)` away from `(x, y)`, if it is inside the grid.
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        offset(self.width, self.height, (x, y), (dx, dy))
+    }
+
+    /// The up to 4 orthogonally adjacent positions inside the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |d| offset(width, height, (x, y), d))
+    }
+
+    /// The up to 8 orthogonally and diagonally adjacent positions inside the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        ALL.into_iter()
+            .filter_map(move |d| offset(width, height, (x, y), d))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// A view of the grid repeating forever in every direction.
+    pub fn wrapping(&self) -> Wrapping<'_, T> {
+        Wrapping { grid: self }
+    }
+
+    /// A view of the grid repeated `across` times horizontally and `down`
+    /// times vertically.
+    pub fn tiled(&self, across: usize, down: usize) -> Tiled<'_, T> {
+        Tiled {
+            grid: self,
+            across,
+            down,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        match self.get(x, y) {
+            Some(cell) => cell,
+            None => panic!(
+                "({}, {}) is outside a {}x{} grid",
+                x, y, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(x, y) {
+            Some(cell) => cell,
+            None => panic!("({}, {}) is outside a {}x{} grid", x, y, width, height),
+        }
+    }
+}
+
+/// Renders one row per line, without a trailing newline.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A toroidal view of a grid: positions past one edge continue from the opposite one.
+#[derive(Clone, Copy)]
+pub struct Wrapping<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> Wrapping<'a, T> {
+    /// The grid position that `(x, y)` wraps around to.
+    pub fn position(&self, x: isize, y: isize) -> (usize, usize) {
+        wrap(self.grid.width, self.grid.height, x, y)
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> &'a T {
+        let grid = self.grid;
+        &grid[self.position(x, y)]
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.grid.width, self.grid.height);
+        ORTHOGONAL
+            .into_iter()
+            .map(move |(dx, dy)| wrap(width, height, x as isize + dx, y as isize + dy))
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.grid.width, self.grid.height);
+        ALL.into_iter()
+            .map(move |(dx, dy)| wrap(width, height, x as isize + dx, y as isize + dy))
+    }
+}
+
+/// A grid repeated a fixed number of times in each direction.
+#[derive(Clone, Copy)]
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+    across: usize,
+    down: usize,
+}
+
+impl<'a, T> Tiled<'a, T> {
+    pub fn width(&self) -> usize {
+        self.grid.width * self.across
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height * self.down
+    }
+
+    /// The cell under `(x, y)` along with the `(x, y)` index of its tile.
+    pub fn get(&self, x: usize, y: usize) -> Option<(&'a T, (usize, usize))> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let grid = self.grid;
+        let cell = &grid[(x % grid.width, y % grid.height)];
+        Some((cell, (x / grid.width, y / grid.height)))
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width(), self.height());
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |d| offset(width, height, (x, y), d))
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width(), self.height());
+        ALL.into_iter()
+            .filter_map(move |d| offset(width, height, (x, y), d))
+    }
+}
@@ -0,0 +1,19 @@
+mod error;
+mod grid;
+mod rng;
+mod search;
+mod stream;
+
+pub use error::ParseError;
+pub use error::Source;
+pub use grid::Grid;
+pub use grid::Tiled;
+pub use grid::Wrapping;
+pub use rng::Rng;
+pub use search::astar;
+pub use search::dijkstra;
+pub use search::Path;
+pub use search::SearchProblem;
+pub use stream::stream_lines;
+pub use stream::StreamError;
+pub use stream::Streaming;
@@ -0,0 +1,122 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A weighted graph searched from a start state for the cheapest way to reach
+/// a goal state.
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+
+    fn start(&self) -> Self::State;
+
+    /// Pushes every state reachable from `state` in one step onto `next`,
+    /// along with the cost of that step.
+    fn successors(&self, state: &Self::State, next: &mut Vec<(Self::State, usize)>);
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// A lower bound on the cost from `state` to the nearest goal, used by
+    /// `astar`. It must never overestimate, and must not drop by more than the
+    /// cost of any single step.
+    fn heuristic(&self, _state: &Self::State) -> usize {
+        0
+    }
+}
+
+/// The cheapest way found to a goal, from the start state to the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+struct Node<S> {
+    state: S,
+    cost: usize,
+    parent: Option<usize>,
+    closed: bool,
+}
+
+fn search<P: SearchProblem>(
+    problem: &P,
+    heuristic: impl Fn(&P::State) -> usize,
+) -> Option<Path<P::State>> {
+    let start = problem.start();
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    let mut nodes = vec![Node {
+        state: start,
+        cost: 0,
+        parent: None,
+        closed: false,
+    }];
+    let mut next = Vec::new();
+
+    while let Some(Reverse((_, i))) = queue.pop() {
+        if nodes[i].closed {
+            continue;
+        }
+        nodes[i].closed = true;
+
+        if problem.is_goal(&nodes[i].state) {
+            return Some(path(&nodes, i));
+        }
+
+        problem.successors(&nodes[i].state, &mut next);
+        for (state, step) in next.drain(..) {
+            let cost = nodes[i].cost + step;
+            let priority = cost + heuristic(&state);
+
+            match index.entry(state) {
+                Entry::Occupied(entry) => {
+                    let node = &mut nodes[*entry.get()];
+                    if node.closed || node.cost <= cost {
+                        continue;
+                    }
+                    node.cost = cost;
+                    node.parent = Some(i);
+                    queue.push(Reverse((priority, *entry.get())));
+                }
+                Entry::Vacant(entry) => {
+                    nodes.push(Node {
+                        state: entry.key().clone(),
+                        cost,
+                        parent: Some(i),
+                        closed: false,
+                    });
+                    entry.insert(nodes.len() - 1);
+                    queue.push(Reverse((priority, nodes.len() - 1)));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn path<S: Clone>(nodes: &[Node<S>], goal: usize) -> Path<S> {
+    let mut states = vec![nodes[goal].state.clone()];
+    let mut i = goal;
+    while let Some(parent) = nodes[i].parent {
+        states.push(nodes[parent].state.clone());
+        i = parent;
+    }
+    states.reverse();
+
+    Path {
+        cost: nodes[goal].cost,
+        states,
+    }
+}
+
+/// Finds a cheapest path to a goal, ignoring the problem's heuristic.
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Option<Path<P::State>> {
+    search(problem, |_| 0)
+}
+
+/// Finds a cheapest path to a goal, guided by the problem's heuristic.
+pub fn astar<P: SearchProblem>(problem: &P) -> Option<Path<P::State>> {
+    search(problem, |state| problem.heuristic(state))
+}
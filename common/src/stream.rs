@@ -0,0 +1,77 @@
+use crate::ParseError;
+use std::error;
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+
+/// A day that can compute its answers while reading its input a line at a
+/// time, without keeping the lines around.
+pub trait Streaming {
+    type Part1;
+    type Part2;
+
+    /// Consumes one line of input, without its line ending. Errors are
+    /// positioned relative to the line.
+    fn feed(&mut self, line: &str) -> Result<(), ParseError>;
+
+    /// The part 1 answer for the lines fed so far.
+    fn part1(&self) -> Self::Part1;
+
+    /// The part 2 answer for the lines fed so far.
+    fn part2(&self) -> Self::Part2;
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => e.fmt(f),
+            StreamError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(e: ParseError) -> Self {
+        StreamError::Parse(e)
+    }
+}
+
+/// Feeds every line of `reader` to `state`, calling `progress` with the
+/// number of lines read after each one, and returns the number of lines.
+///
+/// Only one line is held in memory at a time.
+pub fn stream_lines<S: Streaming>(
+    mut reader: impl BufRead,
+    state: &mut S,
+    mut progress: impl FnMut(usize, &S),
+) -> Result<usize, StreamError> {
+    let mut line = String::new();
+    let mut number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(number);
+        }
+        number += 1;
+
+        let text = line.strip_suffix('\n').unwrap_or(&line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        state.feed(text).map_err(|e| e.on_line(number))?;
+        progress(number, state);
+    }
+}
@@ -0,0 +1,66 @@
+use common::Grid;
+use common::ParseError;
+use common::Source;
+
+fn digits(text: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(&Source::new(0, text), text, "expected a digit")
+}
+
+#[test]
+fn parse_and_display() {
+    let grid = digits("123\n456\n").unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(grid.to_string(), "123\n456");
+}
+
+#[test]
+fn parse_errors() {
+    let error = digits("123\n4x6\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 2));
+    assert_eq!(error.message, "expected a digit");
+
+    let error = digits("123\n45\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
+    assert_eq!(
+        error.message,
+        "rows must all have the same, non-zero length"
+    );
+
+    assert_eq!(digits("").unwrap_err().message, "empty grid");
+}
+
+#[test]
+fn neighbours() {
+    let grid = Grid::new(3, 3, 0);
+
+    let mut corner: Vec<_> = grid.neighbours4(0, 0).collect();
+    corner.sort();
+    assert_eq!(corner, [(0, 1), (1, 0)]);
+    assert_eq!(grid.neighbours8(0, 0).count(), 3);
+    assert_eq!(grid.neighbours4(1, 1).count(), 4);
+    assert_eq!(grid.neighbours8(1, 1).count(), 8);
+}
+
+#[test]
+fn wrapping() {
+    let grid = digits("12\n34\n").unwrap();
+    let wrapping = grid.wrapping();
+
+    assert_eq!(*wrapping.get(-1, 0), 2);
+    assert_eq!(*wrapping.get(2, 3), 3);
+    assert_eq!(wrapping.neighbours4(0, 0).count(), 4);
+    assert!(wrapping.neighbours8(0, 0).all(|(x, y)| x < 2 && y < 2));
+}
+
+#[test]
+fn tiled() {
+    let grid = digits("12\n34\n").unwrap();
+    let tiled = grid.tiled(3, 2);
+
+    assert_eq!((tiled.width(), tiled.height()), (6, 4));
+    assert_eq!(tiled.get(5, 2), Some((&2, (2, 1))));
+    assert_eq!(tiled.get(6, 0), None);
+    assert_eq!(tiled.neighbours4(5, 3).count(), 2);
+}
@@ -0,0 +1,114 @@
+use common::astar;
+use common::dijkstra;
+use common::Path;
+use common::SearchProblem;
+
+/// A small graph with a cheap detour: 0 -> 1 -> 2 -> 3 costs 3, while the
+/// direct edges 0 -> 3 and 0 -> 2 cost 10 and 5.
+struct Graph {
+    edges: Vec<(u32, u32, usize)>,
+    goal: u32,
+}
+
+impl SearchProblem for Graph {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        0
+    }
+
+    fn successors(&self, &state: &u32, next: &mut Vec<(u32, usize)>) {
+        next.extend(
+            self.edges
+                .iter()
+                .filter(|&&(from, _, _)| from == state)
+                .map(|&(_, to, cost)| (to, cost)),
+        );
+    }
+
+    fn is_goal(&self, &state: &u32) -> bool {
+        state == self.goal
+    }
+}
+
+fn graph(goal: u32) -> Graph {
+    Graph {
+        edges: vec![
+            (0, 3, 10),
+            (0, 2, 5),
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 0, 1),
+        ],
+        goal,
+    }
+}
+
+#[test]
+fn cheapest_path() {
+    let expected = Path {
+        cost: 3,
+        states: vec![0, 1, 2, 3],
+    };
+    assert_eq!(dijkstra(&graph(3)), Some(expected.clone()));
+    assert_eq!(astar(&graph(3)), Some(expected));
+}
+
+#[test]
+fn start_is_goal() {
+    let path = dijkstra(&graph(0)).unwrap();
+    assert_eq!((path.cost, path.states), (0, vec![0]));
+}
+
+#[test]
+fn unreachable_goal() {
+    assert_eq!(dijkstra(&graph(4)), None);
+}
+
+/// Walking an open grid from one corner to the other, with an admissible
+/// Manhattan distance heuristic.
+struct Walk {
+    size: usize,
+}
+
+impl SearchProblem for Walk {
+    type State = (usize, usize);
+
+    fn start(&self) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn successors(&self, &(x, y): &(usize, usize), next: &mut Vec<((usize, usize), usize)>) {
+        let cost = 1 + (x * 7 + y * 3) % 5;
+        for (x, y) in [
+            (x + 1, y),
+            (x, y + 1),
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
+        ] {
+            if x < self.size && y < self.size {
+                next.push(((x, y), cost));
+            }
+        }
+    }
+
+    fn is_goal(&self, &state: &(usize, usize)) -> bool {
+        state == (self.size - 1, self.size - 1)
+    }
+
+    fn heuristic(&self, &(x, y): &(usize, usize)) -> usize {
+        (self.size - 1 - x) + (self.size - 1 - y)
+    }
+}
+
+#[test]
+fn astar_agrees_with_dijkstra() {
+    for size in 1..20 {
+        let walk = Walk { size };
+        let (expected, path) = (dijkstra(&walk).unwrap(), astar(&walk).unwrap());
+        assert_eq!(path.cost, expected.cost, "size {}", size);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&(size - 1, size - 1)));
+    }
+}
@@ -1,31 +1,88 @@
-use std::io;
-use std::io::BufRead;
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use common::Streaming;
 
-fn main() {
-    let depths: Vec<u32> = io::stdin()
-        .lock()
-        .lines()
-        .map(|depth| depth.unwrap().parse().unwrap())
-        .collect();
-
-    println!(
-        "part 1: {}",
-        depths
-            .iter()
-            .zip(depths.iter().skip(1))
-            .filter(|(prev, cur)| prev < cur)
-            .count()
-    );
-
-    let mut n = 0usize;
-    let mut s: u32 = depths.iter().take(3).sum();
-    for i in 3..depths.len() {
-        let s1 = s;
-        let s2 = s - depths[i - 3] + depths[i];
-        if s1 < s2 {
-            n += 1;
+const DAY: u32 = 1;
+
+/// Depth increases counted a depth at a time, remembering only the last
+/// three depths.
+#[derive(Default)]
+pub struct Sweep {
+    window: [u32; 3],
+    depths: usize,
+    increases: usize,
+    window_increases: usize,
+}
+
+impl Sweep {
+    pub fn push(&mut self, depth: u32) {
+        if self.depths >= 1 && depth > self.window[(self.depths - 1) % 3] {
+            self.increases += 1;
         }
-        s = s2;
+        // Consecutive windows share two depths, so the sums differ by the
+        // new depth minus the one dropped from the previous window.
+        if self.depths >= 3 && depth > self.window[self.depths % 3] {
+            self.window_increases += 1;
+        }
+
+        self.window[self.depths % 3] = depth;
+        self.depths += 1;
+    }
+}
+
+impl Streaming for Sweep {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn feed(&mut self, line: &str) -> Result<(), ParseError> {
+        let depth = Source::new(DAY, line).parse(line, "depth")?;
+        self.push(depth);
+        Ok(())
+    }
+
+    fn part1(&self) -> usize {
+        self.increases
+    }
+
+    fn part2(&self) -> usize {
+        self.window_increases
     }
-    println!("part 2: {}", n);
+}
+
+pub type Input = Vec<u32>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|depth| source.parse(depth, "depth"))
+        .collect()
+}
+
+fn sweep(depths: &[u32]) -> Sweep {
+    let mut sweep = Sweep::default();
+    for &depth in depths {
+        sweep.push(depth);
+    }
+    sweep
+}
+
+pub fn part1(depths: &[u32]) -> usize {
+    sweep(depths).part1()
+}
+
+pub fn part2(depths: &[u32]) -> usize {
+    sweep(depths).part2()
+}
+
+/// A sonar sweep of `size` depths drifting mostly downwards.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.between(100, 200);
+    (0..size.max(1))
+        .map(|_| {
+            depth = (depth + rng.between(-20, 30)).max(0);
+            format!("{}\n", depth)
+        })
+        .collect()
 }
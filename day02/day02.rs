@@ -1,58 +1,142 @@
-use std::io;
-use std::io::BufRead;
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use common::Streaming;
 
-struct Command(String, u32);
+const DAY: u32 = 2;
 
-struct Submarine {
-    depth: i32,
-    position: i32,
-    aim: i32,
+pub enum Command {
+    Forward(i32),
+    Down(i32),
+    Up(i32),
+}
+
+pub struct Submarine {
+    pub depth: i64,
+    pub position: i64,
+    pub aim: i64,
 }
 
 impl Submarine {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             depth: 0,
             position: 0,
             aim: 0,
         }
     }
+
+    /// The horizontal position times the depth, which cannot overflow.
+    pub fn product(&self) -> i128 {
+        self.position as i128 * self.depth as i128
+    }
 }
 
-fn main() {
-    let commands: Vec<(String, i32)> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| {
-            let line = line.unwrap();
-            let parts: Vec<&str> = line.split(' ').collect();
-            assert!(parts.len() == 2);
-            (parts[0].to_string(), parts[1].parse().unwrap())
-        })
-        .collect();
-
-    let mut sub = Submarine::new();
-    for (instr, value) in &commands {
-        match instr.as_str() {
-            "forward" => sub.position += value,
-            "down" => sub.depth += value,
-            "up" => sub.depth -= value,
-            _ => panic!("invalid command"),
-        }
+impl Default for Submarine {
+    fn default() -> Self {
+        Self::new()
     }
-    println!("part 1: {}", sub.position * sub.depth);
+}
+
+/// Where the submarine ends up under both readings of the commands, steered
+/// a command at a time.
+#[derive(Default)]
+pub struct Course {
+    pub plain: Submarine,
+    pub aimed: Submarine,
+}
 
-    let mut sub = Submarine::new();
-    for (instr, value) in &commands {
-        match instr.as_str() {
-            "forward" => {
-                sub.position += value;
-                sub.depth += sub.aim * value;
+impl Course {
+    /// Follows `command`, or returns `None` if the submarine gets further
+    /// than an `i64` can tell.
+    pub fn steer(&mut self, command: &Command) -> Option<()> {
+        match *command {
+            Command::Forward(value) => {
+                let value = value as i64;
+                self.plain.position = self.plain.position.checked_add(value)?;
+                self.aimed.position = self.aimed.position.checked_add(value)?;
+                let descent = self.aimed.aim.checked_mul(value)?;
+                self.aimed.depth = self.aimed.depth.checked_add(descent)?;
+            }
+            Command::Down(value) => {
+                self.plain.depth = self.plain.depth.checked_add(value as i64)?;
+                self.aimed.aim = self.aimed.aim.checked_add(value as i64)?;
+            }
+            Command::Up(value) => {
+                self.plain.depth = self.plain.depth.checked_sub(value as i64)?;
+                self.aimed.aim = self.aimed.aim.checked_sub(value as i64)?;
             }
-            "down" => sub.aim += value,
-            "up" => sub.aim -= value,
-            _ => panic!("invalid command"),
         }
+        Some(())
     }
-    println!("part 2: {}", sub.position * sub.depth);
+}
+
+impl Streaming for Course {
+    type Part1 = i128;
+    type Part2 = i128;
+
+    fn feed(&mut self, line: &str) -> Result<(), ParseError> {
+        let source = Source::new(DAY, line);
+        let command = parse_command(&source, line)?;
+        self.steer(&command)
+            .ok_or_else(|| source.error(line, "the submarine goes out of range"))
+    }
+
+    fn part1(&self) -> i128 {
+        self.plain.product()
+    }
+
+    fn part2(&self) -> i128 {
+        self.aimed.product()
+    }
+}
+
+fn parse_command(source: &Source, line: &str) -> Result<Command, ParseError> {
+    let (command, value) = line
+        .split_once(' ')
+        .ok_or_else(|| source.error(line, "expected `<command> <value>`"))?;
+    let value = source.parse(value, "value")?;
+    match command {
+        "forward" => Ok(Command::Forward(value)),
+        "down" => Ok(Command::Down(value)),
+        "up" => Ok(Command::Up(value)),
+        _ => Err(source.error(command, "invalid command")),
+    }
+}
+
+pub type Input = Vec<Command>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| parse_command(&source, line))
+        .collect()
+}
+
+/// The course steered by `commands`, if it stays within range.
+fn course(commands: &[Command]) -> Option<Course> {
+    let mut course = Course::default();
+    for command in commands {
+        course.steer(command)?;
+    }
+    Some(course)
+}
+
+pub fn part1(commands: &[Command]) -> Option<i128> {
+    Some(course(commands)?.part1())
+}
+
+pub fn part2(commands: &[Command]) -> Option<i128> {
+    Some(course(commands)?.part2())
+}
+
+/// A course of `size` commands.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let command = rng.pick(&["forward", "forward", "down", "up"]);
+            format!("{} {}\n", command, rng.between(1, 9))
+        })
+        .collect()
 }
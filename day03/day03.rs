@@ -1,9 +1,17 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::cmp::Ordering;
-use std::io;
-use std::io::BufRead;
+use std::collections::HashSet;
 use std::str;
 
-fn count_bits(report: &[Vec<u8>], index: usize, valid: impl Fn(usize) -> bool) -> (usize, usize) {
+const DAY: u32 = 3;
+
+pub fn count_bits(
+    report: &[Vec<u8>],
+    index: usize,
+    valid: impl Fn(usize) -> bool,
+) -> (usize, usize) {
     report
         .iter()
         .enumerate()
@@ -18,7 +26,7 @@ fn count_bits(report: &[Vec<u8>], index: usize, valid: impl Fn(usize) -> bool) -
         })
 }
 
-fn find_gamma_epsilon(
+pub fn find_gamma_epsilon(
     nums: &[Vec<u8>],
     bits: usize,
     bit_criteria: impl Fn(usize, usize) -> u8,
@@ -31,7 +39,7 @@ fn find_gamma_epsilon(
         .collect()
 }
 
-fn find_oxygen_co2(
+pub fn find_oxygen_co2(
     report: &[Vec<u8>],
     num_len: usize,
     bit_criteria: impl Fn(usize, usize) -> u8,
@@ -60,48 +68,95 @@ fn find_oxygen_co2(
     None
 }
 
-fn most_common_bit(zeros: usize, ones: usize) -> u8 {
+pub fn most_common_bit(zeros: usize, ones: usize) -> u8 {
     match zeros.cmp(&ones) {
         Ordering::Less | Ordering::Equal => b'1',
         Ordering::Greater => b'0',
     }
 }
 
-fn least_common_bit(zeros: usize, ones: usize) -> u8 {
+pub fn least_common_bit(zeros: usize, ones: usize) -> u8 {
     match zeros.cmp(&ones) {
         Ordering::Less | Ordering::Equal => b'0',
         Ordering::Greater => b'1',
     }
 }
 
-fn bin2int(num: &[u8]) -> u32 {
+pub fn bin2int(num: &[u8]) -> u32 {
     u32::from_str_radix(str::from_utf8(num).unwrap(), 2).unwrap()
 }
 
-fn main() {
-    let report: Vec<Vec<u8>> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.unwrap().as_bytes().to_vec())
-        .collect();
-    assert!(report.len() > 0);
+pub type Input = Vec<Vec<u8>>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut report: Vec<Vec<u8>> = Vec::new();
+
+    for line in input.lines() {
+        source.check_chars(line, |c| c == '0' || c == '1', "expected a binary digit")?;
+        if let Some(first) = report.first() {
+            if line.len() != first.len() {
+                return Err(source.error(line, format!("expected {} bits", first.len())));
+            }
+        }
+        report.push(line.as_bytes().to_vec());
+    }
+
+    if report.is_empty() {
+        return Err(source.error(source.end(), "empty diagnostic report"));
+    }
+    Ok(report)
+}
+
+pub fn part1(report: &[Vec<u8>]) -> u32 {
     let num_len = report[0].len();
 
-    let gamma_bin = find_gamma_epsilon(&report, num_len, most_common_bit);
+    let gamma_bin = find_gamma_epsilon(report, num_len, most_common_bit);
     let gamma = bin2int(&gamma_bin);
 
-    let epsilon_bin = find_gamma_epsilon(&report, num_len, least_common_bit);
+    let epsilon_bin = find_gamma_epsilon(report, num_len, least_common_bit);
     let epsilon = bin2int(&epsilon_bin);
 
-    println!("part 1: {}", gamma * epsilon);
+    gamma * epsilon
+}
+
+pub fn part2(report: &[Vec<u8>]) -> u32 {
+    let num_len = report[0].len();
 
-    let oxygen_bin = find_oxygen_co2(&report, num_len, most_common_bit)
+    let oxygen_bin = find_oxygen_co2(report, num_len, most_common_bit)
         .expect("could not find oxygen generator rating");
     let oxygen = bin2int(&oxygen_bin);
 
-    let co2_bin = find_oxygen_co2(&report, num_len, least_common_bit)
+    let co2_bin = find_oxygen_co2(report, num_len, least_common_bit)
         .expect("could not find CO2 scrubber rating");
     let co2 = bin2int(&co2_bin);
 
-    println!("part 2: {}", oxygen * co2);
+    oxygen * co2
+}
+
+/// A diagnostic report of `size` distinct numbers from which both ratings
+/// can be found.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let bits = (usize::BITS - size.leading_zeros()) as usize + 3;
+
+    loop {
+        let mut numbers = HashSet::new();
+        while numbers.len() < size {
+            numbers.insert(rng.below(1 << bits));
+        }
+        let report: Vec<Vec<u8>> = numbers
+            .iter()
+            .map(|n| format!("{:0width$b}", n, width = bits).into_bytes())
+            .collect();
+
+        if find_oxygen_co2(&report, bits, most_common_bit).is_some()
+            && find_oxygen_co2(&report, bits, least_common_bit).is_some()
+        {
+            return report
+                .iter()
+                .map(|num| format!("{}\n", str::from_utf8(num).unwrap()))
+                .collect();
+        }
+    }
 }
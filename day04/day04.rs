@@ -1,23 +1,28 @@
-use std::io;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 
-struct BingoBoard {
+const DAY: u32 = 4;
+
+#[derive(Clone)]
+pub struct BingoBoard {
     board: [[u32; 5]; 5],
     marked: [[bool; 5]; 5],
 }
 
 impl BingoBoard {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             board: [[0; 5]; 5],
             marked: [[false; 5]; 5],
         }
     }
 
-    fn set_number(&mut self, row: usize, col: usize, n: u32) {
+    pub fn set_number(&mut self, row: usize, col: usize, n: u32) {
         self.board[row][col] = n;
     }
 
-    fn mark(&mut self, n: u32) -> bool {
+    pub fn mark(&mut self, n: u32) -> bool {
         for row in 0..5 {
             for col in 0..5 {
                 if self.board[row][col] == n {
@@ -31,71 +36,75 @@ impl BingoBoard {
         false
     }
 
-    fn win_row(&self, row: usize) -> bool {
+    pub fn win_row(&self, row: usize) -> bool {
         (0..5).all(|dx| self.marked[row][dx])
     }
 
-    fn win_col(&self, col: usize) -> bool {
+    pub fn win_col(&self, col: usize) -> bool {
         (0..5).all(|dy| self.marked[dy][col])
     }
 
-    fn win(&self) -> bool {
+    pub fn win(&self) -> bool {
         (0..5).any(|n| self.win_row(n) || self.win_col(n))
-
     }
 
-    fn unmarked_numbers_sum(&self) -> u32 {
+    pub fn unmarked_numbers_sum(&self) -> u32 {
         self.board
             .iter()
             .enumerate()
-            .map(|(y, row)| {
+            .flat_map(|(y, row)| {
                 row.iter()
                     .enumerate()
                     .filter(move |&(x, _)| !self.marked[y][x])
                     .map(|(_, n)| *n)
             })
-            .flatten()
             .sum()
     }
 }
 
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+impl Default for BingoBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Input {
+    pub random_numbers: Vec<u32>,
+    pub boards: Vec<BingoBoard>,
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut lines = input.lines();
 
-    let random_numbers: Vec<u32> = input
+    let random_numbers = lines
+        .next()
+        .ok_or_else(|| source.error(source.end(), "expected random numbers"))?
         .split(',')
-        .map(|n| {
-            n.trim()
-                .parse()
-                .expect("error parsing random numbers in input")
-        })
-        .collect();
+        .map(|n| source.parse(n.trim(), "random number"))
+        .collect::<Result<Vec<u32>, _>>()?;
     let mut boards = Vec::new();
 
-    loop {
-        input.clear();
-        match io::stdin().read_line(&mut input) {
-            Ok(0) => break,
-            Ok(1) => (),
-            Ok(_) => panic!("expected empty line in input"),
-            Err(_) => panic!("error while reading input"),
+    while let Some(line) = lines.next() {
+        if !line.is_empty() {
+            return Err(source.error(line, "expected empty line"));
         }
 
         let mut board = BingoBoard::new();
 
         for y in 0..5 {
-            input.clear();
-            io::stdin()
-                .read_line(&mut input)
-                .expect("error while reading board from input");
-            let nums: Vec<u32> = input
+            let line = lines
+                .next()
+                .ok_or_else(|| source.error(source.end(), "expected board row"))?;
+            let nums = line
                 .split(' ')
                 .map(|n| n.trim())
-                .filter(|n| n.len() > 0)
-                .map(|n| n.parse().expect("error parsing number from board in input"))
-                .collect();
-            assert!(nums.len() == 5);
+                .filter(|n| !n.is_empty())
+                .map(|n| source.parse(n, "board number"))
+                .collect::<Result<Vec<u32>, _>>()?;
+            if nums.len() != 5 {
+                return Err(source.error(line, "expected 5 numbers per board row"));
+            }
             for (x, n) in nums.iter().enumerate() {
                 board.set_number(y, x, *n);
             }
@@ -104,14 +113,22 @@ fn main() {
         boards.push(board);
     }
 
+    Ok(Input {
+        random_numbers,
+        boards,
+    })
+}
+
+pub fn play(random_numbers: &[u32], boards: &[BingoBoard]) -> (Option<u32>, Option<u32>) {
+    let mut boards = boards.to_vec();
     let mut first_to_win_score: Option<u32> = None;
     let mut last_to_win_score: Option<u32> = None;
 
-    for random_number in random_numbers {
+    for &random_number in random_numbers {
         for board in boards.iter_mut().filter(|board| !board.win()) {
             if board.mark(random_number) {
                 let score = board.unmarked_numbers_sum() * random_number;
-                if let None = first_to_win_score {
+                if first_to_win_score.is_none() {
                     first_to_win_score = Some(score);
                 }
                 last_to_win_score = Some(score);
@@ -119,6 +136,34 @@ fn main() {
         }
     }
 
-    println!("part 1: {}", first_to_win_score.unwrap());
-    println!("part 2: {}", last_to_win_score.unwrap());
+    (first_to_win_score, last_to_win_score)
+}
+
+/// The score of the first board to win, or `None` if none does.
+pub fn part1(input: &Input) -> Option<u32> {
+    play(&input.random_numbers, &input.boards).0
+}
+
+/// The score of the last board to win, or `None` if none does.
+pub fn part2(input: &Input) -> Option<u32> {
+    play(&input.random_numbers, &input.boards).1
+}
+
+/// A game of `size` boards, drawing every number so that all boards win.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u32> = (0..100).collect();
+    rng.shuffle(&mut numbers);
+    let draws: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+    let mut out = draws.join(",") + "\n";
+
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut numbers);
+        out.push('\n');
+        for row in numbers[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+            out += &(row.join(" ") + "\n");
+        }
+    }
+
+    out
 }
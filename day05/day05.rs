@@ -1,50 +1,56 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::io;
-use std::io::BufRead;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
+const DAY: u32 = 5;
+
 #[derive(PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
 }
 
 impl FromStr for Point {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let coords: Vec<&str> = s.split(',').collect();
-        assert_eq!(coords.len(), 2);
+        let source = Source::new(DAY, s);
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| source.error(s, "expected `x,y`"))?;
 
-        let x = coords[0].parse::<i32>()?;
-        let y = coords[1].parse::<i32>()?;
+        let x = source.parse(x, "x coordinate")?;
+        let y = source.parse(y, "y coordinate")?;
 
         Ok(Point { x, y })
     }
 }
 
-struct Segment {
-    start: Point,
-    end: Point,
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
 }
 
 impl FromStr for Segment {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let points: Vec<&str> = s.split(" -> ").collect();
-        assert_eq!(points.len(), 2);
+        let source = Source::new(DAY, s);
+        let (start, end) = s
+            .split_once(" -> ")
+            .ok_or_else(|| source.error(s, "expected `x1,y1 -> x2,y2`"))?;
 
-        let start = points[0].parse::<Point>()?;
-        let end = points[1].parse::<Point>()?;
+        let start = source.parse_part(start)?;
+        let end = source.parse_part(end)?;
 
         Ok(Segment { start, end })
     }
 }
 
-fn count_intersections(
+pub fn count_intersections(
     segments: &[Segment],
     direction: impl Fn(&Point, &Point) -> Option<(i32, i32)>,
 ) -> usize {
@@ -69,43 +75,71 @@ fn count_intersections(
     points_cnt.values().filter(|&cnt| *cnt > 1).count()
 }
 
-fn main() {
-    let segments: Vec<Segment> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.unwrap().parse().expect("error parsing input"))
-        .collect();
-
-    println!(
-        "part 1: {}",
-        count_intersections(&segments, |start, end| {
-            match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
-                (Ordering::Less, Ordering::Equal) => Some((1, 0)),
-                (Ordering::Equal, Ordering::Less) => Some((0, 1)),
-                (Ordering::Equal, Ordering::Equal) => Some((0, 0)),
-                (Ordering::Equal, Ordering::Greater) => Some((0, -1)),
-                (Ordering::Greater, Ordering::Equal) => Some((-1, 0)),
-                _ => None,
-            }
-        })
-    );
-
-    println!(
-        "part 2: {}",
-        count_intersections(&segments, |start, end| {
-            match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
-                (Ordering::Less, Ordering::Less) => Some((1, 1)),
-                (Ordering::Less, Ordering::Equal) => Some((1, 0)),
-                (Ordering::Less, Ordering::Greater) => Some((1, -1)),
-
-                (Ordering::Equal, Ordering::Less) => Some((0, 1)),
-                (Ordering::Equal, Ordering::Equal) => Some((0, 0)),
-                (Ordering::Equal, Ordering::Greater) => Some((0, -1)),
-
-                (Ordering::Greater, Ordering::Less) => Some((-1, 1)),
-                (Ordering::Greater, Ordering::Equal) => Some((-1, 0)),
-                (Ordering::Greater, Ordering::Greater) => Some((-1, -1)),
-            }
+pub type Input = Vec<Segment>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input.lines().map(|line| source.parse_part(line)).collect()
+}
+
+pub fn part1(segments: &[Segment]) -> usize {
+    count_intersections(segments, |start, end| {
+        match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
+            (Ordering::Less, Ordering::Equal) => Some((1, 0)),
+            (Ordering::Equal, Ordering::Less) => Some((0, 1)),
+            (Ordering::Equal, Ordering::Equal) => Some((0, 0)),
+            (Ordering::Equal, Ordering::Greater) => Some((0, -1)),
+            (Ordering::Greater, Ordering::Equal) => Some((-1, 0)),
+            _ => None,
+        }
+    })
+}
+
+pub fn part2(segments: &[Segment]) -> usize {
+    count_intersections(segments, |start, end| {
+        match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
+            (Ordering::Less, Ordering::Less) => Some((1, 1)),
+            (Ordering::Less, Ordering::Equal) => Some((1, 0)),
+            (Ordering::Less, Ordering::Greater) => Some((1, -1)),
+
+            (Ordering::Equal, Ordering::Less) => Some((0, 1)),
+            (Ordering::Equal, Ordering::Equal) => Some((0, 0)),
+            (Ordering::Equal, Ordering::Greater) => Some((0, -1)),
+
+            (Ordering::Greater, Ordering::Less) => Some((-1, 1)),
+            (Ordering::Greater, Ordering::Equal) => Some((-1, 0)),
+            (Ordering::Greater, Ordering::Greater) => Some((-1, -1)),
+        }
+    })
+}
+
+/// `size` horizontal, vertical and diagonal lines of vents.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let max = (2 * size).max(10) as i32;
+    let room = |p: i32, d: i32| match d {
+        1 => max - p,
+        -1 => p,
+        _ => max,
+    };
+
+    (0..size.max(1))
+        .map(|_| {
+            let (x, y) = (
+                rng.between(0, max as i64) as i32,
+                rng.between(0, max as i64) as i32,
+            );
+            let (dx, dy) = *rng.pick(&[
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ]);
+            let len = rng.between(0, room(x, dx).min(room(y, dy)) as i64) as i32;
+            format!("{},{} -> {},{}\n", x, y, x + dx * len, y + dy * len)
         })
-    );
+        .collect()
 }
@@ -1,24 +1,48 @@
-use std::io;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 
-fn fishes_after(timers: &[usize; 9], days: usize) -> usize {
-    let mut timers = timers.clone();
+const DAY: u32 = 6;
+
+/// The number of lanternfish after `days`, or `None` if there are too many
+/// to count in a `usize`.
+pub fn fishes_after(timers: &[usize; 9], days: usize) -> Option<usize> {
+    let mut timers = *timers;
     for _ in 0..days {
         timers.rotate_left(1);
-        timers[6] += timers[8];
+        timers[6] = timers[6].checked_add(timers[8])?;
     }
-    timers.iter().sum()
+    timers.iter().try_fold(0usize, |sum, &n| sum.checked_add(n))
 }
 
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+pub type Input = [usize; 9];
 
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
     let mut timers = [0usize; 9];
-    for n in input.split(',') {
-        let n: usize = n.trim().parse().expect("error reading input");
-        timers[n] += 1;
+    for n in input.trim().split(',') {
+        let n = n.trim();
+        let timer: usize = source.parse(n, "timer")?;
+        if timer >= timers.len() {
+            return Err(source.error(n, "timer must be between 0 and 8"));
+        }
+        timers[timer] += 1;
     }
+    Ok(timers)
+}
+
+pub fn part1(timers: &Input) -> Option<usize> {
+    fishes_after(timers, 80)
+}
+
+pub fn part2(timers: &Input) -> Option<usize> {
+    fishes_after(timers, 256)
+}
 
-    println!("part 1: {}", fishes_after(&timers, 80));
-    println!("part 2: {}", fishes_after(&timers, 256));
+/// A school of `size` lanternfish.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let timers: Vec<String> = (0..size.max(1))
+        .map(|_| rng.between(0, 6).to_string())
+        .collect();
+    timers.join(",") + "\n"
 }
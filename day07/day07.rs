@@ -1,47 +1,61 @@
-use std::io;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 
-fn fuel_usage_sum(to: u32, from: &[u32], fuel_usage: impl Fn(u32, u32) -> u32) -> u32 {
+const DAY: u32 = 7;
+
+pub fn fuel_usage_sum(to: u32, from: &[u32], fuel_usage: impl Fn(u32, u32) -> u32) -> u32 {
     from.iter().map(|&from| fuel_usage(from, to)).sum()
 }
 
-fn solve(positions: &[u32], fuel_usage: impl Fn(u32, u32) -> u32) -> u32 {
+/// The fuel used to align all crabs on the best position. The total fuel
+/// is convex in the target position, so the search looks for where it stops
+/// decreasing.
+pub fn solve(positions: &[u32], fuel_usage: impl Fn(u32, u32) -> u32) -> u32 {
     let mut low = *positions.iter().min().unwrap();
     let mut hi = *positions.iter().max().unwrap();
 
     while low < hi {
-        let mid = (low + hi + 1) / 2;
-        let low_cost = fuel_usage_sum(low, &positions, &fuel_usage);
-        let hi_cost = fuel_usage_sum(hi, &positions, &fuel_usage);
-        if low_cost < hi_cost {
-            hi = mid - 1;
-        } else if hi_cost < low_cost {
-            low = mid;
+        let mid = low + (hi - low) / 2;
+        if fuel_usage_sum(mid, positions, &fuel_usage)
+            <= fuel_usage_sum(mid + 1, positions, &fuel_usage)
+        {
+            hi = mid;
         } else {
-            hi -= 1;
+            low = mid + 1;
         }
     }
 
-    fuel_usage_sum(low, &positions, fuel_usage)
+    fuel_usage_sum(low, positions, fuel_usage)
 }
 
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+pub type Input = Vec<u32>;
 
-    let crabs_positions: Vec<u32> = input
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .trim()
         .split(',')
-        .map(|n| n.trim().parse().expect("error reading input"))
-        .collect();
+        .map(|n| source.parse(n.trim(), "crab position"))
+        .collect()
+}
+
+pub fn part1(positions: &[u32]) -> u32 {
+    solve(positions, |from, to| to.abs_diff(from))
+}
+
+pub fn part2(positions: &[u32]) -> u32 {
+    solve(positions, |from, to| {
+        let diff = to.abs_diff(from);
+        (diff * (diff + 1)) / 2
+    })
+}
 
-    println!(
-        "part 1: {}",
-        solve(&crabs_positions, |from, to| to.abs_diff(from))
-    );
-    println!(
-        "part 2: {}",
-        solve(&crabs_positions, |from, to| {
-            let diff = to.abs_diff(from);
-            (diff * (diff + 1)) / 2
-        })
-    );
+/// `size` crab positions.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let max = (4 * size).max(10) as i64;
+    let positions: Vec<String> = (0..size.max(1))
+        .map(|_| rng.between(0, max).to_string())
+        .collect();
+    positions.join(",") + "\n"
 }
@@ -1,20 +1,24 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use common::Streaming;
 use std::collections::HashMap;
 use std::convert::TryInto;
-use std::io;
-use std::io::BufRead;
 use std::str::FromStr;
 
+const DAY: u32 = 8;
+
 #[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
-struct SignalPattern {
+pub struct SignalPattern {
     segments: [bool; 7],
 }
 
 impl SignalPattern {
-    fn active_segments(&self) -> usize {
+    pub fn active_segments(&self) -> usize {
         self.segments.iter().filter(|&&segment| segment).count()
     }
 
-    fn intersection_len(&self, other: &Self) -> usize {
+    pub fn intersection_len(&self, other: &Self) -> usize {
         self.segments
             .iter()
             .zip(other.segments.iter())
@@ -23,23 +27,19 @@ impl SignalPattern {
     }
 }
 
-#[derive(Debug)]
-struct ParseSignalPatterError;
-
 impl FromStr for SignalPattern {
-    type Err = ParseSignalPatterError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() == 0 || s.len() > 7 {
-            return Err(Self::Err {});
+        let source = Source::new(DAY, s);
+        if s.is_empty() || s.len() > 7 {
+            return Err(source.error(s, "expected between 1 and 7 segments"));
         }
+        source.check_chars(s, |c| ('a'..='g').contains(&c), "invalid segment")?;
 
         let mut segments = [false; 7];
         for c in s.bytes() {
-            match c {
-                b'a'..=b'g' => segments[(c - b'a') as usize] = true,
-                _ => return Err(Self::Err {}),
-            }
+            segments[(c - b'a') as usize] = true;
         }
 
         Ok(SignalPattern { segments })
@@ -47,39 +47,40 @@ impl FromStr for SignalPattern {
 }
 
 #[derive(Debug)]
-struct Display {
-    signals: [SignalPattern; 10],
-    output_values: [SignalPattern; 4],
+pub struct Display {
+    pub signals: [SignalPattern; 10],
+    pub output_values: [SignalPattern; 4],
 }
 
-#[derive(Debug)]
-struct ParseDisplayError;
-
 impl FromStr for Display {
-    type Err = ParseDisplayError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<Vec<SignalPattern>> = s
-            .split(" | ")
-            .map(|patterns| {
-                patterns
-                    .split(' ')
-                    .map(|pattern| pattern.parse().unwrap())
-                    .collect()
-            })
-            .collect();
-        if parts.len() == 2 && parts[0].len() == 10 && parts[1].len() == 4 {
-            Ok(Display {
-                signals: parts[0].clone().try_into().unwrap(),
-                output_values: parts[1].clone().try_into().unwrap(),
-            })
-        } else {
-            Err(Self::Err {})
-        }
+        let source = Source::new(DAY, s);
+        let (signals, output_values) = s
+            .split_once(" | ")
+            .ok_or_else(|| source.error(s, "expected `<signals> | <output values>`"))?;
+
+        let patterns = |group: &str, count: usize| -> Result<Vec<SignalPattern>, ParseError> {
+            let patterns = group
+                .split(' ')
+                .map(|pattern| source.parse_part(pattern))
+                .collect::<Result<Vec<_>, _>>()?;
+            if patterns.len() == count {
+                Ok(patterns)
+            } else {
+                Err(source.error(group, format!("expected {} patterns", count)))
+            }
+        };
+
+        Ok(Display {
+            signals: patterns(signals, 10)?.try_into().unwrap(),
+            output_values: patterns(output_values, 4)?.try_into().unwrap(),
+        })
     }
 }
 
-fn find_unique(
+pub fn find_unique(
     patterns: &[SignalPattern],
     constraints: impl Fn(&SignalPattern) -> bool,
 ) -> Option<SignalPattern> {
@@ -95,7 +96,7 @@ fn find_unique(
 }
 
 impl Display {
-    fn decode_signals(&self) -> Option<HashMap<SignalPattern, u32>> {
+    pub fn decode_signals(&self) -> Option<HashMap<SignalPattern, u32>> {
         let one = find_unique(&self.signals, |signal| signal.active_segments() == 2)?;
         let four = find_unique(&self.signals, |signal| signal.active_segments() == 4)?;
         let seven = find_unique(&self.signals, |signal| signal.active_segments() == 3)?;
@@ -127,10 +128,10 @@ impl Display {
             );
         }
 
-        return Some(decoded);
+        Some(decoded)
     }
 
-    fn decode_ouput_values(&self) -> Option<[u32; 4]> {
+    pub fn decode_ouput_values(&self) -> Option<[u32; 4]> {
         let decoded = self.decode_signals()?;
         Some([
             *decoded.get(&self.output_values[0])?,
@@ -141,39 +142,122 @@ impl Display {
     }
 }
 
-fn main() {
-    let displays: Vec<Display> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.unwrap().parse().expect("error parsing input"))
-        .collect();
+pub type Input = Vec<Display>;
 
-    println!(
-        "part 1: {}",
-        displays
-            .iter()
-            .map(|display| {
-                display
-                    .output_values
-                    .iter()
-                    .filter(|value| match value.active_segments() {
-                        2 | 3 | 4 | 7 => true,
-                        _ => false,
-                    })
-                    .count()
-            })
-            .sum::<usize>()
-    );
-
-    println!(
-        "part 2: {}",
-        displays
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input.lines().map(|line| source.parse_part(line)).collect()
+}
+
+/// Running totals over the displays seen so far, a display at a time.
+pub struct Notes {
+    easy_digits: usize,
+    output_sum: Option<u64>,
+}
+
+impl Notes {
+    pub fn new() -> Self {
+        Self {
+            easy_digits: 0,
+            output_sum: Some(0),
+        }
+    }
+
+    /// Adds a display; once one cannot be decoded, or the sum no longer
+    /// fits in a `u64`, there is no output sum.
+    pub fn push(&mut self, display: &Display) {
+        self.easy_digits += display
+            .output_values
             .iter()
-            .map(|display| display
-                .decode_ouput_values()
-                .expect("could not decode output values")
+            .filter(|value| matches!(value.active_segments(), 2 | 3 | 4 | 7))
+            .count();
+
+        let value = display
+            .decode_ouput_values()
+            .map(|values| values.iter().fold(0, |acc, value| acc * 10 + value));
+        self.output_sum = self
+            .output_sum
+            .zip(value)
+            .and_then(|(sum, value)| sum.checked_add(value as u64));
+    }
+}
+
+impl Default for Notes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Streaming for Notes {
+    type Part1 = usize;
+    type Part2 = Option<u64>;
+
+    fn feed(&mut self, line: &str) -> Result<(), ParseError> {
+        self.push(&line.parse()?);
+        Ok(())
+    }
+
+    fn part1(&self) -> usize {
+        self.easy_digits
+    }
+
+    fn part2(&self) -> Option<u64> {
+        self.output_sum
+    }
+}
+
+fn notes(displays: &[Display]) -> Notes {
+    let mut notes = Notes::new();
+    for display in displays {
+        notes.push(display);
+    }
+    notes
+}
+
+pub fn part1(displays: &[Display]) -> usize {
+    notes(displays).part1()
+}
+
+/// The sum of the output values, or `None` if some display cannot be
+/// decoded.
+pub fn part2(displays: &[Display]) -> Option<u64> {
+    notes(displays).part2()
+}
+
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+fn generate_pattern(rng: &mut Rng, wiring: &[u8], digit: usize) -> String {
+    let mut segments: Vec<u8> = DIGITS[digit]
+        .bytes()
+        .map(|segment| wiring[(segment - b'a') as usize])
+        .collect();
+    rng.shuffle(&mut segments);
+    String::from_utf8(segments).unwrap()
+}
+
+/// `size` displays, each with its own random wiring.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let mut wiring: Vec<u8> = (b'a'..=b'g').collect();
+            rng.shuffle(&mut wiring);
+
+            let mut digits: Vec<usize> = (0..10).collect();
+            rng.shuffle(&mut digits);
+            let signals: Vec<String> = digits
                 .iter()
-                .fold(0, |acc, value| acc * 10 + value))
-            .sum::<u32>()
-    );
+                .map(|&digit| generate_pattern(rng, &wiring, digit))
+                .collect();
+            let output: Vec<String> = (0..4)
+                .map(|_| {
+                    let digit = rng.below(10);
+                    generate_pattern(rng, &wiring, digit)
+                })
+                .collect();
+
+            format!("{} | {}\n", signals.join(" "), output.join(" "))
+        })
+        .collect()
 }
@@ -1,87 +1,75 @@
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashSet;
-use std::io;
-use std::io::BufRead;
 
-fn within_bounds(y: isize, x: isize, heightmap: &[Vec<u32>]) -> bool {
-    y >= 0 && (y as usize) < heightmap.len() && x >= 0 && (x as usize) < heightmap[y as usize].len()
-}
-
-fn find_low_points(heightmap: &[Vec<u32>]) -> Vec<(usize, usize)> {
-    let mut low_points = Vec::new();
-
-    for y in 0..heightmap.len() {
-        for x in 0..heightmap[y].len() {
-            let h = heightmap[y][x];
-            let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-            if directions
-                .iter()
-                .map(|(dy, dx)| (y as isize + dy, x as isize + dx))
-                .filter(|&(y, x)| within_bounds(y, x, &heightmap))
-                .all(|(y, x)| h < heightmap[y as usize][x as usize])
-            {
-                low_points.push((y, x));
-            }
-        }
-    }
+const DAY: u32 = 9;
 
-    return low_points;
+pub fn find_low_points(heightmap: &Grid<u32>) -> Vec<(usize, usize)> {
+    heightmap
+        .positions()
+        .filter(|&(x, y)| {
+            let h = heightmap[(x, y)];
+            heightmap
+                .neighbours4(x, y)
+                .all(|neighbour| h < heightmap[neighbour])
+        })
+        .collect()
 }
 
-fn basin_size(
-    y: usize,
+pub fn basin_size(
     x: usize,
-    heightmap: &[Vec<u32>],
+    y: usize,
+    heightmap: &Grid<u32>,
     visited: &mut HashSet<(usize, usize)>,
 ) -> usize {
-    let h = heightmap[y][x];
+    let h = heightmap[(x, y)];
     let mut size = 1;
-    let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-
-    for (dy, dx) in directions {
-        let y = y as isize + dy;
-        let x = x as isize + dx;
 
-        if within_bounds(y, x, heightmap) {
-            let y = y as usize;
-            let x = x as usize;
-            if !visited.contains(&(y, x)) && heightmap[y][x] > h && heightmap[y][x] != 9 {
-                visited.insert((y, x));
-                size += basin_size(y, x, heightmap, visited);
-            }
+    for (x, y) in heightmap.neighbours4(x, y) {
+        if !visited.contains(&(x, y)) && heightmap[(x, y)] > h && heightmap[(x, y)] != 9 {
+            visited.insert((x, y));
+            size += basin_size(x, y, heightmap, visited);
         }
     }
 
-    return size;
+    size
 }
 
-fn main() {
-    let heightmap: Vec<Vec<u32>> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| {
-            line.unwrap()
-                .trim()
-                .bytes()
-                .map(|c| (c - b'0') as u32)
-                .collect()
-        })
-        .collect();
+pub type Input = Grid<u32>;
 
-    let low_points = find_low_points(&heightmap);
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    Grid::parse_digits(&source, input, "expected a height digit")
+}
 
-    println!(
-        "part 1: {}",
-        low_points
-            .iter()
-            .map(|&(y, x)| heightmap[y][x] + 1)
-            .sum::<u32>()
-    );
+pub fn part1(heightmap: &Grid<u32>) -> u32 {
+    find_low_points(heightmap)
+        .iter()
+        .map(|&p| heightmap[p] + 1)
+        .sum()
+}
 
-    let mut basins: Vec<usize> = low_points
+pub fn part2(heightmap: &Grid<u32>) -> usize {
+    let mut basins: Vec<usize> = find_low_points(heightmap)
         .iter()
-        .map(|&(y, x)| basin_size(y, x, &heightmap, &mut HashSet::new()))
+        .map(|&(x, y)| basin_size(x, y, heightmap, &mut HashSet::new()))
         .collect();
     basins.sort();
 
-    println!("part 2: {}", basins.iter().rev().take(3).product::<usize>());
+    basins.iter().rev().take(3).product()
+}
+
+/// A `size` by `size` heightmap.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| {
+            let row: String = (0..size)
+                .map(|_| char::from(b'0' + rng.below(10) as u8))
+                .collect();
+            row + "\n"
+        })
+        .collect()
 }
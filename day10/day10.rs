@@ -1,64 +1,205 @@
-use std::io;
-use std::io::BufRead;
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use common::Streaming;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
-fn main() {
-    let lines: Vec<String> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.unwrap())
-        .collect();
+const DAY: u32 = 10;
+
+pub enum Line {
+    Corrupted(char),
+    Incomplete(Vec<char>),
+    Complete,
+}
 
-    let mut error_score = 0;
-    let mut completion_scores: Vec<u64> = Vec::new();
+pub fn check(line: &str) -> Result<Line, ParseError> {
+    let source = Source::new(DAY, line);
+    let mut stack = Vec::new();
 
-    for line in lines {
-        let mut stack = Vec::new();
-        let mut corrupted = false;
-
-        for c in line.chars() {
-            match c {
-                '(' => stack.push(')'),
-                '[' => stack.push(']'),
-                '{' => stack.push('}'),
-                '<' => stack.push('>'),
-                ')' | ']' | '}' | '>' => {
-                    if let Some(expected) = stack.pop() {
-                        if c != expected {
-                            corrupted = true;
-                            error_score += match c {
-                                ')' => 3,
-                                ']' => 57,
-                                '}' => 1197,
-                                '>' => 25137,
-                                _ => unreachable!(),
-                            };
-                            break;
-                        }
-                    } else {
-                        panic!("unexpected extra closing character");
+    for (i, c) in line.char_indices() {
+        let at = &line[i..i + c.len_utf8()];
+        match c {
+            '(' => stack.push(')'),
+            '[' => stack.push(']'),
+            '{' => stack.push('}'),
+            '<' => stack.push('>'),
+            ')' | ']' | '}' | '>' => {
+                if let Some(expected) = stack.pop() {
+                    if c != expected {
+                        return Ok(Line::Corrupted(c));
                     }
+                } else {
+                    return Err(source.error(at, "unexpected extra closing character"));
                 }
-                _ => panic!("invalid character"),
             }
+            _ => return Err(source.error(at, "invalid character")),
         }
+    }
 
-        if !corrupted && stack.len() > 0 {
-            completion_scores.push(stack.iter().rev().fold(0, |score, expected| {
-                score * 5
-                    + match expected {
-                        ')' => 1,
-                        ']' => 2,
-                        '}' => 3,
-                        '>' => 4,
-                        _ => unreachable!(),
-                    }
-            }));
+    if stack.is_empty() {
+        Ok(Line::Complete)
+    } else {
+        stack.reverse();
+        Ok(Line::Incomplete(stack))
+    }
+}
+
+pub fn error_score(c: char) -> u64 {
+    match c {
+        ')' => 3,
+        ']' => 57,
+        '}' => 1197,
+        '>' => 25137,
+        _ => unreachable!(),
+    }
+}
+
+pub fn completion_score(completion: &[char]) -> u64 {
+    completion.iter().fold(0, |score, expected| {
+        score * 5
+            + match expected {
+                ')' => 1,
+                ']' => 2,
+                '}' => 3,
+                '>' => 4,
+                _ => unreachable!(),
+            }
+    })
+}
+
+/// Scores of the lines checked so far, a line at a time. Only the completion
+/// scores of incomplete lines are kept, split in two halves around their
+/// middle one so that it can be found after every line.
+#[derive(Default)]
+pub struct Scores {
+    error_score: u64,
+    /// The lower half of the completion scores, with the middle one if
+    /// there is an odd number of them.
+    lower: BinaryHeap<u64>,
+    upper: BinaryHeap<Reverse<u64>>,
+}
+
+impl Scores {
+    pub fn push(&mut self, line: &Line) {
+        match line {
+            Line::Corrupted(c) => self.error_score += error_score(*c),
+            Line::Incomplete(completion) => self.push_completion(completion_score(completion)),
+            Line::Complete => {}
+        }
+    }
+
+    fn push_completion(&mut self, score: u64) {
+        match self.lower.peek() {
+            Some(&middle) if score > middle => self.upper.push(Reverse(score)),
+            _ => self.lower.push(score),
+        }
+
+        // Keep the lower half as large as the upper one, or one larger.
+        if self.lower.len() > self.upper.len() + 1 {
+            let score = self.lower.pop().unwrap();
+            self.upper.push(Reverse(score));
+        } else if self.upper.len() > self.lower.len() {
+            let Reverse(score) = self.upper.pop().unwrap();
+            self.lower.push(score);
+        }
+    }
+
+    /// The middle completion score, if there is an odd number of them.
+    pub fn middle_score(&self) -> Option<u64> {
+        if self.lower.len() == self.upper.len() {
+            return None;
+        }
+        self.lower.peek().copied()
+    }
+}
+
+impl Streaming for Scores {
+    type Part1 = u64;
+    type Part2 = Option<u64>;
+
+    fn feed(&mut self, line: &str) -> Result<(), ParseError> {
+        self.push(&check(line)?);
+        Ok(())
+    }
+
+    fn part1(&self) -> u64 {
+        self.error_score
+    }
+
+    fn part2(&self) -> Option<u64> {
+        self.middle_score()
+    }
+}
+
+pub type Input = Vec<Line>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|line| check(line).map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+fn scores(lines: &[Line]) -> Scores {
+    let mut scores = Scores::default();
+    for line in lines {
+        scores.push(line);
+    }
+    scores
+}
+
+pub fn part1(lines: &[Line]) -> u64 {
+    scores(lines).part1()
+}
+
+/// The middle completion score, or `None` without an odd number of
+/// incomplete lines.
+pub fn part2(lines: &[Line]) -> Option<u64> {
+    scores(lines).middle_score()
+}
+
+const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+fn generate_line(rng: &mut Rng, corrupted: bool) -> String {
+    let len = rng.between(8, 40) as usize;
+    let mut line = String::new();
+    let mut stack = Vec::new();
+
+    while line.len() < len || stack.is_empty() {
+        if stack.is_empty() || rng.chance(3, 5) {
+            let &(open, close) = rng.pick(&PAIRS);
+            line.push(open);
+            stack.push(close);
+        } else {
+            line.push(stack.pop().unwrap());
         }
     }
 
-    println!("part 1: {}", error_score);
+    if corrupted {
+        let expected = stack.pop().unwrap();
+        let wrong: Vec<char> = PAIRS
+            .iter()
+            .map(|&(_, close)| close)
+            .filter(|&close| close != expected)
+            .collect();
+        line.push(*rng.pick(&wrong));
+    }
+
+    line
+}
+
+/// `size` corrupted or incomplete lines, with an odd number of incomplete
+/// ones so that the middle completion score exists.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let incomplete = 2 * rng.below(size.div_ceil(2)) + 1;
+
+    let mut corrupted: Vec<bool> = (0..size).map(|i| i >= incomplete).collect();
+    rng.shuffle(&mut corrupted);
 
-    completion_scores.sort();
-    assert_eq!(completion_scores.len() % 2, 1);
-    println!("part 2: {}", completion_scores[completion_scores.len() / 2]);
+    corrupted
+        .into_iter()
+        .map(|corrupted| generate_line(rng, corrupted) + "\n")
+        .collect()
 }
@@ -1,85 +1,89 @@
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashSet;
-use std::io;
-use std::io::BufRead;
 
-fn within_bounds(y: isize, x: isize, grid: &Vec<Vec<u32>>) -> bool {
-    y >= 0 && (y as usize) < grid.len() && x >= 0 && (x as usize) < grid[y as usize].len()
-}
+const DAY: u32 = 11;
 
-fn step(grid: &mut Vec<Vec<u32>>) -> usize {
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            grid[y][x] += 1;
-        }
+pub fn step(grid: &mut Grid<u32>) -> usize {
+    for energy in grid.iter_mut() {
+        *energy += 1;
     }
 
     let mut flashed = HashSet::new();
 
     loop {
-        let mut flashing = Vec::new();
-
-        for y in 0..grid.len() {
-            for x in 0..grid[y].len() {
-                if grid[y][x] > 9 && !flashed.contains(&(y, x)) {
-                    flashing.push((y, x));
-                }
-            }
-        }
+        let flashing: Vec<(usize, usize)> = grid
+            .positions()
+            .filter(|&p| grid[p] > 9 && !flashed.contains(&p))
+            .collect();
 
-        if flashing.len() == 0 {
+        if flashing.is_empty() {
             return flashed.len();
         }
 
-        for &(y, x) in &flashing {
-            flashed.insert((y, x));
-
-            for dy in -1..=1 {
-                for dx in -1..=1 {
-                    let y = (y as isize) - dy;
-                    let x = (x as isize) - dx;
-                    if within_bounds(y, x, grid) {
-                        grid[y as usize][x as usize] += 1;
-                    }
-                }
+        for &(x, y) in &flashing {
+            flashed.insert((x, y));
+
+            for neighbour in grid.neighbours8(x, y) {
+                grid[neighbour] += 1;
             }
         }
 
-        for &(y, x) in &flashed {
-            grid[y][x] = 0;
+        for &p in &flashed {
+            grid[p] = 0;
         }
     }
 }
 
-fn main() {
-    let mut grid: Vec<Vec<u32>> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.unwrap().bytes().map(|b| (b - b'0') as u32).collect())
-        .collect();
-
-    let len = grid.len() * grid[0].len();
-    let mut total_flashes = 0;
-    let mut all_flashed = None;
-
-    for s in 1..=100 {
-        let flashes = step(&mut grid);
-        total_flashes += flashes;
-        if flashes == len {
-            all_flashed = Some(s);
-        }
-    }
+pub type Input = Grid<u32>;
 
-    println!("part 1: {}", total_flashes);
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    Grid::parse_digits(&source, input, "expected an energy level digit")
+}
 
-    if all_flashed.is_none() {
-        for s in 101.. {
-            let flashes = step(&mut grid);
-            if flashes == len {
-                all_flashed = Some(s);
-                break;
-            }
+pub fn flashes_after(grid: &Grid<u32>, steps: usize) -> usize {
+    let mut grid = grid.clone();
+
+    (1..=steps).map(|_| step(&mut grid)).sum()
+}
+
+pub fn part1(grid: &Grid<u32>) -> usize {
+    flashes_after(grid, 100)
+}
+
+pub fn part2(grid: &Grid<u32>) -> usize {
+    let mut grid = grid.clone();
+    let len = grid.len();
+
+    (1..).find(|_| step(&mut grid) == len).unwrap()
+}
+
+/// A `size` by `size` grid of octopuses that eventually all flash together.
+///
+/// Random grids usually synchronize, but less often and more slowly the
+/// larger they are, so each gets fewer steps to do so. After a few that
+/// don't, the grid falls back to two neighbouring energy levels: the first
+/// octopuses to flash push all the others over the edge, so they flash
+/// together on that same step.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let steps = 1_000_000 / (size * size);
+
+    for _ in 0..10 {
+        let grid = Grid::from_fn(size, size, |_, _| rng.below(10) as u32);
+
+        let mut simulated = grid.clone();
+        if (0..steps).any(|_| step(&mut simulated) == simulated.len()) {
+            return grid.to_string() + "\n";
         }
     }
 
-    println!("part 2: {}", all_flashed.unwrap());
+    let low = rng.below(9) as u32;
+    let mut grid = Grid::from_fn(size, size, |_, _| low + rng.below(2) as u32);
+    let first = (rng.below(size), rng.below(size));
+    grid[first] = low + 1;
+    grid.to_string() + "\n"
 }
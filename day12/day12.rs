@@ -1,26 +1,42 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::io;
-use std::io::BufRead;
 
-fn main() {
+const DAY: u32 = 12;
+
+pub type Input = HashMap<String, Vec<String>>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
     let mut adacency_list: HashMap<String, Vec<String>> = HashMap::new();
 
-    for line in io::stdin().lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split('-').collect();
-        assert_eq!(parts.len(), 2);
+    for line in input.lines() {
+        let (a, b) = line
+            .split_once('-')
+            .ok_or_else(|| source.error(line, "expected `<cave>-<cave>`"))?;
+        for cave in [a, b] {
+            if cave.is_empty() {
+                return Err(source.error(cave, "expected a cave name"));
+            }
+            source.check_chars(cave, |c| c.is_ascii_alphabetic(), "invalid cave name")?;
+        }
 
-        let a = parts[0].to_string();
-        let b = parts[1].to_string();
+        let a = a.to_string();
+        let b = b.to_string();
 
-        adacency_list
-            .entry(a.clone())
-            .or_insert(Vec::new())
-            .push(b.clone());
-        adacency_list.entry(b).or_insert(Vec::new()).push(a);
+        adacency_list.entry(a.clone()).or_default().push(b.clone());
+        adacency_list.entry(b).or_default().push(a);
     }
 
+    if !adacency_list.contains_key("start") {
+        return Err(source.error(source.end(), "missing `start` cave"));
+    }
+    Ok(adacency_list)
+}
+
+pub fn part1(adacency_list: &Input) -> usize {
     let mut path_count = 0;
     let mut stack: Vec<(String, HashSet<String>)> = Vec::new();
 
@@ -44,8 +60,10 @@ fn main() {
         }
     }
 
-    println!("part 1: {}", path_count);
+    path_count
+}
 
+pub fn part2(adacency_list: &Input) -> usize {
     let mut path_count = 0;
     let mut stack: Vec<(String, HashSet<String>, bool)> = Vec::new();
 
@@ -79,5 +97,57 @@ fn main() {
         }
     }
 
-    println!("part 2: {}", path_count);
+    path_count
+}
+
+fn cave_name(rng: &mut Rng, big: bool, taken: &HashSet<String>) -> String {
+    loop {
+        let letters = if big { b'A'..=b'Z' } else { b'a'..=b'z' };
+        let letters: Vec<u8> = letters.collect();
+        let name: String = (0..2).map(|_| char::from(*rng.pick(&letters))).collect();
+        if !taken.contains(&name) && name != "start" && name != "end" {
+            return name;
+        }
+    }
+}
+
+/// A connected cave system with `size` small caves and a few big ones, no
+/// two big caves adjacent so that the number of paths stays finite.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut taken = HashSet::new();
+    let mut caves = vec!["start".to_string()];
+
+    for i in 0..size + size.div_ceil(3) {
+        let name = cave_name(rng, i >= size, &taken);
+        taken.insert(name.clone());
+        caves.push(name);
+    }
+    rng.shuffle(&mut caves[1..]);
+    caves.push("end".to_string());
+
+    let big = |i: usize| caves[i].as_bytes()[0].is_ascii_uppercase();
+    let mut edges = HashSet::new();
+
+    for i in 1..caves.len() {
+        let linked: Vec<usize> = (0..i).filter(|&j| !(big(i) && big(j))).collect();
+        edges.insert((*rng.pick(&linked), i));
+    }
+    for _ in 0..size / 2 {
+        let (a, b) = (rng.below(caves.len()), rng.below(caves.len()));
+        if a < b && !(big(a) && big(b)) {
+            edges.insert((a, b));
+        }
+    }
+
+    let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
+    edges.sort();
+    rng.shuffle(&mut edges);
+    edges
+        .into_iter()
+        .map(|(a, b)| {
+            let (a, b) = if rng.chance(1, 2) { (a, b) } else { (b, a) };
+            format!("{}-{}\n", caves[a], caves[b])
+        })
+        .collect()
 }
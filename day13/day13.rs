@@ -1,196 +1,216 @@
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashSet;
 use std::fmt;
-use std::io;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: usize,
-    y: usize,
-}
+const DAY: u32 = 13;
 
-#[derive(Debug)]
-enum PointParseError {
-    Malformed,
-    ParseIntError(ParseIntError),
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
 }
 
 impl FromStr for Point {
-    type Err = PointParseError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let coords: Vec<&str> = s.split(',').collect();
-        if coords.len() != 2 {
-            return Err(Self::Err::Malformed);
-        }
+        let source = Source::new(DAY, s);
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| source.error(s, "expected `x,y`"))?;
 
-        let x = coords[0]
-            .parse()
-            .or_else(|e| Err(Self::Err::ParseIntError(e)))?;
-        let y = coords[1]
-            .parse()
-            .or_else(|e| Err(Self::Err::ParseIntError(e)))?;
+        let x = source.parse(x, "x coordinate")?;
+        let y = source.parse(y, "y coordinate")?;
 
         Ok(Self { x, y })
     }
 }
 
-enum Axis {
+pub enum Axis {
     X,
     Y,
 }
 
-struct Instruction {
-    axis: Axis,
-    coord: usize,
-}
-
-#[derive(Debug)]
-enum InstructionParseError {
-    Malformed,
-    InvalidAxis(char),
-    ParseIntError(ParseIntError),
+pub struct Instruction {
+    pub axis: Axis,
+    pub coord: usize,
 }
 
 impl FromStr for Instruction {
-    type Err = InstructionParseError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.trim().split('=').collect();
-        if parts.len() != 2 {
-            return Err(Self::Err::Malformed);
-        }
-
-        let axis = match parts[0].chars().last() {
-            Some('x') => Axis::X,
-            Some('y') => Axis::Y,
-            Some(c) => return Err(Self::Err::InvalidAxis(c)),
-            None => return Err(Self::Err::Malformed),
+        let source = Source::new(DAY, s);
+        let (axis, coord) = s
+            .trim()
+            .split_once('=')
+            .ok_or_else(|| source.error(s, "expected `fold along <axis>=<coord>`"))?;
+
+        let axis = match axis.char_indices().last() {
+            Some((_, 'x')) => Axis::X,
+            Some((_, 'y')) => Axis::Y,
+            Some((i, _)) => return Err(source.error(&axis[i..], "invalid axis")),
+            None => return Err(source.error(s, "expected `fold along <axis>=<coord>`")),
         };
-        let coord = parts[1]
-            .parse()
-            .or_else(|e| Err(Self::Err::ParseIntError(e)))?;
+        let coord = source.parse(coord, "fold coordinate")?;
 
         Ok(Self { axis, coord })
     }
 }
 
-struct Page {
-    dots: HashSet<Point>,
-    height: usize,
-    width: usize,
+#[derive(Clone)]
+pub struct Page {
+    dots: Grid<bool>,
 }
 
 impl Page {
-    fn new(dots: HashSet<Point>) -> Self {
+    pub fn new(dots: HashSet<Point>) -> Self {
         let width = dots.iter().map(|p| p.x).max().unwrap() + 1;
         let height = dots.iter().map(|p| p.y).max().unwrap() + 1;
         Self {
-            dots,
-            height,
-            width,
+            dots: Grid::from_fn(width, height, |x, y| dots.contains(&Point { x, y })),
         }
     }
 
-    fn within_page(&self, p: &Point) -> bool {
-        p.x < self.width && p.y < self.height
+    fn dot(&self, x: usize, y: usize) -> bool {
+        self.dots.get(x, y).copied().unwrap_or(false)
     }
 
-    fn fold_up(&mut self, along: usize) {
-        let bottom_part: Vec<Point> = self
-            .dots
-            .iter()
-            .filter(|p| self.within_page(p) && p.y > along)
-            .copied()
-            .collect();
-        for p in bottom_part {
-            self.dots.insert(Point {
-                x: p.x,
-                y: along - (p.y - along),
-            });
-        }
-        self.height = along;
-    }
-
-    fn fold_left(&mut self, along: usize) {
-        let right_part: Vec<Point> = self
-            .dots
-            .iter()
-            .filter(|p| self.within_page(p) && p.x > along)
-            .copied()
-            .collect();
-        for p in right_part {
-            self.dots.insert(Point {
-                x: along - (p.x - along),
-                y: p.y,
-            });
-        }
-        self.width = along;
+    pub fn fold_up(&mut self, along: usize) {
+        self.dots = Grid::from_fn(self.dots.width(), along, |x, y| {
+            self.dot(x, y) || self.dot(x, along + (along - y))
+        });
+    }
+
+    pub fn fold_left(&mut self, along: usize) {
+        self.dots = Grid::from_fn(along, self.dots.height(), |x, y| {
+            self.dot(x, y) || self.dot(along + (along - x), y)
+        });
     }
 
-    fn visible_dots(&self) -> usize {
-        self.dots.iter().filter(|p| self.within_page(p)).count()
+    pub fn visible_dots(&self) -> usize {
+        self.dots.iter().filter(|&&dot| dot).count()
     }
 }
 
 impl fmt::Display for Page {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.dots.contains(&Point { x, y }) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            if y != self.height - 1 {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        let page = self.dots.map(|&dot| if dot { '#' } else { '.' });
+        write!(f, "{}", page)
     }
 }
 
-fn apply_instruction(page: &mut Page, instruction: &Instruction) {
+pub fn apply_instruction(page: &mut Page, instruction: &Instruction) {
     match instruction.axis {
         Axis::X => page.fold_left(instruction.coord),
         Axis::Y => page.fold_up(instruction.coord),
     }
 }
 
-fn main() {
+pub struct Input {
+    pub page: Page,
+    pub instructions: Vec<Instruction>,
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut lines = input.lines();
     let mut dots: HashSet<Point> = HashSet::new();
 
-    loop {
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        if input.len() <= 1 {
+    for line in lines.by_ref() {
+        if line.is_empty() {
             break;
         }
-        dots.insert(input.trim().parse().expect("error parsing dot in input"));
+        dots.insert(source.parse_part(line.trim())?);
+    }
+    if dots.is_empty() {
+        return Err(source.error(input, "expected at least one dot"));
     }
 
-    let mut instructions: Vec<Instruction> = Vec::new();
-
-    loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(0) => break,
-            Ok(_) => instructions.push(input.parse().expect("error parsing instruction in input")),
-            Err(e) => panic!("{}", e),
-        }
+    let instructions = lines
+        .map(|line| source.parse_part(line))
+        .collect::<Result<Vec<Instruction>, _>>()?;
+    if instructions.is_empty() {
+        return Err(source.error(source.end(), "expected at least one fold instruction"));
     }
 
-    let mut page = Page::new(dots);
+    Ok(Input {
+        page: Page::new(dots),
+        instructions,
+    })
+}
+
+pub fn part1(input: &Input) -> usize {
+    let mut page = input.page.clone();
 
-    apply_instruction(&mut page, &instructions[0]);
+    apply_instruction(&mut page, &input.instructions[0]);
 
-    println!("part 1: {}", page.visible_dots());
+    page.visible_dots()
+}
 
-    for instruction in &instructions[1..] {
+pub fn part2(input: &Input) -> Page {
+    let mut page = input.page.clone();
+
+    for instruction in &input.instructions {
         apply_instruction(&mut page, instruction);
     }
 
-    println!("part 2:\n{}", page);
+    page
+}
+
+/// Whether folding `(x, y)` along `folds` in order puts it on a fold line.
+fn on_fold_line(mut x: usize, mut y: usize, folds: &[(char, usize)]) -> bool {
+    for &(axis, along) in folds {
+        let coord = if axis == 'x' { &mut x } else { &mut y };
+        if *coord == along {
+            return true;
+        }
+        if *coord > along {
+            *coord = 2 * along - *coord;
+        }
+    }
+    false
+}
+
+/// A page with `size / 4 + 2` folds, each halving the page, and dots that
+/// never lie on a fold line.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut width = rng.between(4, 8) as usize;
+    let mut height = rng.between(5, 7) as usize;
+    let mut folds = Vec::new();
+
+    for _ in 0..size / 4 + 2 {
+        if rng.chance(1, 2) {
+            folds.push(('x', width));
+            width = 2 * width + 1;
+        } else {
+            folds.push(('y', height));
+            height = 2 * height + 1;
+        }
+    }
+    folds.reverse();
+
+    let mut dots = HashSet::new();
+    let count = (10 * size.max(1)).min(width * height / 3);
+    while dots.len() < count {
+        let (x, y) = (rng.below(width), rng.below(height));
+        if !on_fold_line(x, y, &folds) {
+            dots.insert((x, y));
+        }
+    }
+
+    let mut dots: Vec<(usize, usize)> = dots.into_iter().collect();
+    dots.sort();
+    rng.shuffle(&mut dots);
+
+    let dots: String = dots.iter().map(|(x, y)| format!("{},{}\n", x, y)).collect();
+    let folds: String = folds
+        .iter()
+        .map(|(axis, along)| format!("fold along {}={}\n", axis, along))
+        .collect();
+    dots + "\n" + &folds
 }
@@ -1,99 +1,152 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashMap;
-use std::io;
-use std::io::BufRead;
-use std::str;
+use std::hash::Hash;
 
-fn step(
+const DAY: u32 = 14;
+
+/// Adds `n` to the count of `key`, or returns `None` if it overflows.
+fn add<K: Eq + Hash>(counts: &mut HashMap<K, usize>, key: K, n: usize) -> Option<()> {
+    let count = counts.entry(key).or_insert(0);
+    *count = count.checked_add(n)?;
+    Some(())
+}
+
+/// The pair counts after one step, or `None` if they overflow.
+pub fn step(
     formula: &HashMap<(u8, u8), usize>,
     rules: &HashMap<(u8, u8), u8>,
-) -> HashMap<(u8, u8), usize> {
+) -> Option<HashMap<(u8, u8), usize>> {
     let mut result = HashMap::new();
 
-    for (pair, n) in formula {
-        if let Some(&insertion) = rules.get(pair) {
-            *result.entry((pair.0, insertion)).or_insert(0) += n;
-            *result.entry((insertion, pair.1)).or_insert(0) += n;
+    for (&pair, &n) in formula {
+        if let Some(&insertion) = rules.get(&pair) {
+            add(&mut result, (pair.0, insertion), n)?;
+            add(&mut result, (insertion, pair.1), n)?;
         }
     }
 
-    result
+    Some(result)
 }
 
-fn count_occurences(formula: &HashMap<(u8, u8), usize>) -> HashMap<u8, usize> {
+pub fn count_occurences(formula: &HashMap<(u8, u8), usize>) -> Option<HashMap<u8, usize>> {
     let mut counter = HashMap::new();
-    for (&(a, b), n) in formula {
-        *counter.entry(a).or_insert(0) += n;
-        *counter.entry(b).or_insert(0) += n;
+    for (&(a, b), &n) in formula {
+        add(&mut counter, a, n)?;
+        add(&mut counter, b, n)?;
     }
-    counter
+    Some(counter)
 }
 
-fn most_common_element_count(counter: &HashMap<u8, usize>) -> usize {
+pub fn most_common_element_count(counter: &HashMap<u8, usize>) -> usize {
     let occurences = counter.values().max().unwrap();
-    if occurences % 2 == 0 {
+    if occurences.is_multiple_of(2) {
         occurences / 2
     } else {
         occurences / 2 + 1
     }
 }
 
-fn least_common_element_count(counter: &HashMap<u8, usize>) -> usize {
+pub fn least_common_element_count(counter: &HashMap<u8, usize>) -> usize {
     let occurences = counter.values().min().unwrap();
-    if occurences % 2 == 0 {
+    if occurences.is_multiple_of(2) {
         occurences / 2
     } else {
         occurences / 2 + 1
     }
 }
 
-fn main() {
-    let mut input = String::new();
-
-    io::stdin()
-        .read_line(&mut input)
-        .expect("error reading polymer template");
-    let template = input.trim();
+pub struct Input {
+    pub formula: HashMap<(u8, u8), usize>,
+    pub rules: HashMap<(u8, u8), u8>,
+}
 
-    let rules: HashMap<(u8, u8), u8> = io::stdin()
-        .lock()
-        .lines()
-        .skip(1)
-        .map(|line| {
-            let line = line.unwrap();
-            let parts: Vec<&str> = line.trim().split(" -> ").collect();
-            assert_eq!(parts.len(), 2);
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut lines = input.lines();
+    let template = lines
+        .next()
+        .ok_or_else(|| source.error(source.end(), "expected polymer template"))?
+        .trim();
+    if template.len() < 2 {
+        return Err(source.error(template, "polymer template needs at least two elements"));
+    }
+    source.check_chars(template, |c| c.is_ascii_uppercase(), "invalid element")?;
 
-            let pair = parts[0].as_bytes();
-            assert_eq!(pair.len(), 2);
-            let insertion = parts[1].as_bytes();
-            assert_eq!(insertion.len(), 1);
+    match lines.next() {
+        Some("") => (),
+        Some(line) => return Err(source.error(line, "expected empty line")),
+        None => return Err(source.error(source.end(), "expected empty line")),
+    }
 
-            ((pair[0], pair[1]), insertion[0])
+    let rules = lines
+        .map(|line| {
+            let (pair, insertion) = line
+                .trim()
+                .split_once(" -> ")
+                .ok_or_else(|| source.error(line, "expected `<pair> -> <element>`"))?;
+
+            if pair.len() != 2 {
+                return Err(source.error(pair, "expected a pair of elements"));
+            }
+            source.check_chars(pair, |c| c.is_ascii_uppercase(), "invalid element")?;
+            if insertion.len() != 1 {
+                return Err(source.error(insertion, "expected a single element"));
+            }
+            source.check_chars(insertion, |c| c.is_ascii_uppercase(), "invalid element")?;
+
+            let pair = pair.as_bytes();
+            Ok(((pair[0], pair[1]), insertion.as_bytes()[0]))
         })
-        .collect();
+        .collect::<Result<HashMap<(u8, u8), u8>, _>>()?;
 
     let mut formula = HashMap::new();
     for pair in template.bytes().zip(template.bytes().skip(1)) {
         *formula.entry(pair).or_insert(0) += 1;
     }
 
-    for _ in 1..=10 {
-        formula = step(&formula, &rules);
+    Ok(Input { formula, rules })
+}
+
+/// The difference between the most and least common elements after
+/// `steps`, or `None` if the polymer grows too long to count in a `usize`.
+pub fn solve(input: &Input, steps: usize) -> Option<usize> {
+    let mut formula = input.formula.clone();
+
+    for _ in 1..=steps {
+        formula = step(&formula, &input.rules)?;
     }
 
-    let counter = count_occurences(&formula);
-    println!(
-        "part 1: {}",
-        most_common_element_count(&counter) - least_common_element_count(&counter)
-    );
+    let counter = count_occurences(&formula)?;
+    Some(most_common_element_count(&counter) - least_common_element_count(&counter))
+}
+
+pub fn part1(input: &Input) -> Option<usize> {
+    solve(input, 10)
+}
 
-    for _ in 11..=40 {
-        formula = step(&formula, &rules);
+pub fn part2(input: &Input) -> Option<usize> {
+    solve(input, 40)
+}
+
+/// A template of `size` elements and a rule for every pair of elements.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut elements: Vec<u8> = (b'A'..=b'Z').collect();
+    rng.shuffle(&mut elements);
+    elements.truncate(2 + (size / 4).min(8));
+
+    let template: String = (0..size.max(2))
+        .map(|_| char::from(*rng.pick(&elements)))
+        .collect();
+    let mut out = template + "\n\n";
+
+    for &a in &elements {
+        for &b in &elements {
+            let insertion = *rng.pick(&elements);
+            out += &format!("{}{} -> {}\n", a as char, b as char, insertion as char);
+        }
     }
 
-    let counter = count_occurences(&formula);
-    println!(
-        "part 2: {}",
-        most_common_element_count(&counter) - least_common_element_count(&counter)
-    );
+    out
 }
@@ -1,83 +1,90 @@
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
-use std::io;
-use std::io::BufRead;
+use common::astar;
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::SearchProblem;
+use common::Source;
+use common::Tiled;
 
-struct Cavern {
-    map: Vec<Vec<usize>>,
+const DAY: u32 = 15;
+
+pub struct Cavern {
+    pub map: Grid<usize>,
 }
 
 impl Cavern {
-    fn get(&self, y: usize, x: usize) -> usize {
-        let tile_y = y / self.map.len();
-        let y = y % self.map.len();
-        let tile_x = x / self.map.len();
-        let x = x % self.map.len();
-
-        let risk = self.map[y][x] + tile_y + tile_x;
-        if risk > 9 {
-            risk % 9
-        } else {
-            risk
-        }
+    pub fn tiled(&self, tiles: usize) -> Tiled<'_, usize> {
+        self.map.tiled(tiles, tiles)
+    }
+}
+
+fn risk(tiled: &Tiled<usize>, x: usize, y: usize) -> usize {
+    let (&risk, (tile_x, tile_y)) = tiled.get(x, y).unwrap();
+    let risk = risk + tile_y + tile_x;
+    if risk > 9 {
+        risk % 9
+    } else {
+        risk
     }
 }
 
-fn lowest_total_risk(cavern: &Cavern, len: usize) -> usize {
-    let mut dist: Vec<Vec<usize>> = (0..len)
-        .map(|_| (0..len).map(|_| usize::MAX).collect())
-        .collect();
-    dist[0][0] = 0;
-
-    let mut q = BinaryHeap::new();
-    q.push(Reverse((dist[0][0], 0, 0)));
-
-    while let Some(Reverse((cost, y, x))) = q.pop() {
-        if y == len - 1 && x == len - 1 {
-            return cost;
-        }
-
-        if cost > dist[y][x] {
-            continue;
-        }
-
-        for (dy, dx) in [(-1, 0), (0, -1), (0, 1), (1, 0)] {
-            let next_x = (x as isize) + dx;
-            let next_y = (y as isize) + dy;
-            if next_x >= 0 && (next_x as usize) < len && next_y >= 0 && (next_y as usize) < len {
-                let next_x = next_x as usize;
-                let next_y = next_y as usize;
-                let next_cost = cost + cavern.get(next_y, next_x);
-
-                if next_cost < dist[next_y][next_x] {
-                    q.push(Reverse((next_cost, next_y, next_x)));
-                    dist[next_y][next_x] = next_cost;
-                }
-            }
-        }
+/// The way from the top left to the bottom right of a tiled cavern.
+pub struct Route<'a> {
+    tiled: Tiled<'a, usize>,
+}
+
+impl SearchProblem for Route<'_> {
+    type State = (usize, usize);
+
+    fn start(&self) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn successors(&self, &(x, y): &(usize, usize), next: &mut Vec<((usize, usize), usize)>) {
+        next.extend(
+            self.tiled
+                .neighbours4(x, y)
+                .map(|(x, y)| ((x, y), risk(&self.tiled, x, y))),
+        );
+    }
+
+    fn is_goal(&self, &(x, y): &(usize, usize)) -> bool {
+        (x, y) == (self.tiled.width() - 1, self.tiled.height() - 1)
     }
 
-    unreachable!()
+    /// Every position entered has a risk of at least 1.
+    fn heuristic(&self, &(x, y): &(usize, usize)) -> usize {
+        (self.tiled.width() - 1 - x) + (self.tiled.height() - 1 - y)
+    }
+}
+
+pub fn lowest_total_risk(cavern: &Cavern, tiles: usize) -> usize {
+    let route = Route {
+        tiled: cavern.tiled(tiles),
+    };
+    astar(&route).unwrap().cost
+}
+
+pub type Input = Cavern;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let map = Grid::parse_digits(&source, input, "expected a risk level digit")?;
+
+    Ok(Cavern { map })
+}
+
+pub fn part1(cavern: &Cavern) -> usize {
+    lowest_total_risk(cavern, 1)
+}
+
+pub fn part2(cavern: &Cavern) -> usize {
+    lowest_total_risk(cavern, 5)
 }
 
-fn main() {
-    let map: Vec<Vec<usize>> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| {
-            line.unwrap()
-                .trim()
-                .bytes()
-                .map(|b| (b - b'0') as usize)
-                .collect()
-        })
-        .collect();
-
-    let cavern = Cavern { map };
-
-    println!("part 1: {}", lowest_total_risk(&cavern, cavern.map.len()));
-    println!(
-        "part 2: {}",
-        lowest_total_risk(&cavern, cavern.map.len() * 5)
-    );
+/// A `size` by `size` map of risk levels.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let map = Grid::from_fn(size, size, |_, _| rng.between(1, 9));
+    map.to_string() + "\n"
 }
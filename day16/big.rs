@@ -0,0 +1,167 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops;
+
+/// A non-negative integer of any size.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Least significant first, with no trailing zeros.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn trimmed(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number of bits needed to write the number, 0 for zero.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => 32 * self.limbs.len() - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Hex digit `i`, counting from the least significant.
+    pub fn nibble(&self, i: usize) -> u8 {
+        self.limbs
+            .get(i / 8)
+            .map_or(0, |limb| (limb >> (4 * (i % 8)) & 0xf) as u8)
+    }
+
+    /// Appends a hex digit, multiplying the number by 16 and adding `nibble`.
+    pub fn push_nibble(&mut self, nibble: u8) {
+        let mut carry = (nibble & 0xf) as u32;
+        for limb in &mut self.limbs {
+            let next = *limb >> 28;
+            *limb = *limb << 4 | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |n, &limb| n << 32 | limb as u128),
+        )
+    }
+
+    pub fn to_usize(&self) -> Option<usize> {
+        self.to_u128()?.try_into().ok()
+    }
+
+    /// Divides in place by `divisor`, returning the remainder.
+    fn divide(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let n = remainder << 32 | *limb as u64;
+            *limb = (n / divisor as u64) as u32;
+            remainder = n % divisor as u64;
+        }
+        *self = Self::trimmed(std::mem::take(&mut self.limbs));
+        remainder as u32
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(n: u128) -> Self {
+        Self::trimmed((0..4).map(|i| (n >> (32 * i)) as u32).collect())
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(n: usize) -> Self {
+        Self::from(n as u128)
+    }
+}
+
+impl From<bool> for BigUint {
+    fn from(b: bool) -> Self {
+        Self::from(b as u128)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let sum = carry
+                + *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::trimmed(limbs)
+    }
+}
+
+impl ops::Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let n = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = n as u32;
+                carry = n >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::trimmed(limbs)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Nine decimal digits at a time, least significant first.
+        let mut n = self.clone();
+        let mut chunks = vec![n.divide(1_000_000_000)];
+        while !n.is_zero() {
+            chunks.push(n.divide(1_000_000_000));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
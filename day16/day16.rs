@@ -1,96 +1,244 @@
-use std::io;
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use std::error;
+use std::fmt;
+use std::ops::Range;
 
-#[derive(Debug)]
-struct Packet {
-    version: usize,
-    type_id: usize,
-    val: Value,
+mod big;
+mod encode;
+mod print;
+mod reader;
+
+pub use big::BigUint;
+pub use encode::EncodeError;
+pub use encode::LengthType;
+pub use print::infix;
+pub use print::sexpr;
+pub use print::tree;
+pub use reader::BitReader;
+
+const DAY: u32 = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: usize,
+    pub type_id: usize,
+    pub val: Value,
 }
 
-#[derive(Debug)]
-enum Value {
-    Literal(usize),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Literal(BigUint),
     Operator(Vec<Packet>),
 }
 
+/// Why a transmission cannot be decoded or its packet evaluated. Offsets
+/// count bits from the start of the transmission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    UnexpectedEnd {
+        offset: usize,
+    },
+    InvalidTypeId {
+        type_id: usize,
+    },
+    /// An operator with no sub-packets, or a comparison without exactly two.
+    OperandCount {
+        type_id: usize,
+        count: usize,
+    },
+    /// Sub-packets of an operator with length type ID 0, starting at
+    /// `offset`, running past their `length` bits.
+    LengthOverrun {
+        offset: usize,
+        length: usize,
+    },
+    /// The value of a packet does not fit in a `usize`. `path` holds the
+    /// sub-packet indices leading to it from the outermost packet.
+    Overflow {
+        path: Vec<usize>,
+    },
+    /// A packet, starting at `offset`, nested more than `MAX_DEPTH` deep.
+    TooDeep {
+        offset: usize,
+    },
+}
+
+impl PacketError {
+    fn offset(&self) -> Option<usize> {
+        match self {
+            PacketError::UnexpectedEnd { offset }
+            | PacketError::LengthOverrun { offset, .. }
+            | PacketError::TooDeep { offset } => Some(*offset),
+            PacketError::InvalidTypeId { .. }
+            | PacketError::OperandCount { .. }
+            | PacketError::Overflow { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PacketError::UnexpectedEnd { offset } => {
+                write!(f, "bit {}: unexpected end of transmission", offset)
+            }
+            PacketError::InvalidTypeId { type_id } => write!(f, "invalid type ID {}", type_id),
+            PacketError::OperandCount { type_id, count } => write!(
+                f,
+                "operator with type ID {} cannot take {} sub-packets",
+                type_id, count
+            ),
+            PacketError::LengthOverrun { offset, length } => write!(
+                f,
+                "bit {}: sub-packets run past their length of {} bits",
+                offset, length
+            ),
+            PacketError::TooDeep { offset } => {
+                write!(
+                    f,
+                    "bit {}: packets nested more than {} deep",
+                    offset, MAX_DEPTH
+                )
+            }
+            PacketError::Overflow { path } if path.is_empty() => {
+                write!(f, "value overflows {} bits", usize::BITS)
+            }
+            PacketError::Overflow { path } => {
+                let path: Vec<String> = path.iter().map(|i| i.to_string()).collect();
+                write!(
+                    f,
+                    "value of sub-packet {} overflows {} bits",
+                    path.join("."),
+                    usize::BITS
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for PacketError {}
+
+/// How deep packets may nest, so that parsing, evaluating and printing them
+/// cannot overflow the stack.
+pub const MAX_DEPTH: usize = 1000;
+
+/// Where a packet lies in a transmission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub bits: Range<usize>,
+    /// For an operator packet, how it gives the size of its sub-packets,
+    /// and that size in bits or sub-packets.
+    pub length: Option<(LengthType, usize)>,
+}
+
 #[derive(Debug)]
-struct Parser {
-    s: Vec<u8>,
-    pos: usize,
+pub struct Parser {
+    reader: BitReader,
+    spans: Vec<Span>,
 }
 
 impl Parser {
-    fn new(s: Vec<u8>) -> Self {
-        Self { s, pos: 0 }
+    pub fn new(reader: BitReader) -> Self {
+        Self {
+            reader,
+            spans: Vec::new(),
+        }
+    }
+
+    /// The spans of the packets parsed so far, in the order their headers
+    /// were read: each packet before its sub-packets.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    pub fn into_spans(self) -> Vec<Span> {
+        self.spans
     }
 
-    fn next(&mut self) -> u8 {
-        let b = self.s[self.pos];
-        self.pos += 1;
-        b
+    pub fn parse_packet(&mut self) -> Result<Packet, PacketError> {
+        self.parse_nested(0)
     }
 
-    fn parse_packet(&mut self) -> Packet {
-        let version = self.parse_version();
-        let type_id = self.parse_type_id();
+    /// Parses a packet with `depth` packets around it.
+    fn parse_nested(&mut self, depth: usize) -> Result<Packet, PacketError> {
+        let start = self.reader.position();
+        if depth == MAX_DEPTH {
+            return Err(PacketError::TooDeep { offset: start });
+        }
+        let index = self.spans.len();
+        self.spans.push(Span {
+            bits: start..start,
+            length: None,
+        });
+
+        let version = self.parse_version()?;
+        let type_id = self.parse_type_id()?;
         let val = if type_id == 4 {
-            Value::Literal(self.parse_literal())
+            Value::Literal(self.parse_literal()?)
         } else {
-            let lenght_type_id = self.next();
+            let lenght_type_id = self.read_number(1)?;
             let mut sub_packets = Vec::new();
 
             if lenght_type_id == 0 {
-                let sub_packets_len = self.read_number(15);
-                let end = self.pos + sub_packets_len;
-                while self.pos < end {
-                    sub_packets.push(self.parse_packet());
+                let sub_packets_len = self.read_number(15)?;
+                self.spans[index].length = Some((LengthType::Bits, sub_packets_len));
+                let start = self.reader.position();
+                let end = start + sub_packets_len;
+                while self.reader.position() < end {
+                    sub_packets.push(self.parse_nested(depth + 1)?);
+                }
+                if self.reader.position() > end {
+                    return Err(PacketError::LengthOverrun {
+                        offset: start,
+                        length: sub_packets_len,
+                    });
                 }
             } else {
-                let sub_packets_cnt = self.read_number(11);
+                let sub_packets_cnt = self.read_number(11)?;
+                self.spans[index].length = Some((LengthType::Count, sub_packets_cnt));
                 for _ in 0..sub_packets_cnt {
-                    sub_packets.push(self.parse_packet());
+                    sub_packets.push(self.parse_nested(depth + 1)?);
                 }
             }
             Value::Operator(sub_packets)
         };
+        self.spans[index].bits.end = self.reader.position();
 
-        Packet {
+        Ok(Packet {
             version,
             type_id,
             val,
-        }
+        })
     }
 
-    fn read_number(&mut self, bits: usize) -> usize {
-        let mut n: usize = 0;
-        for _ in 0..bits {
-            n = (n << 1) | (self.next() as usize);
-        }
-        n
+    fn read_number(&mut self, bits: usize) -> Result<usize, PacketError> {
+        Ok(self.reader.read(bits)? as usize)
     }
 
-    fn parse_version(&mut self) -> usize {
+    fn parse_version(&mut self) -> Result<usize, PacketError> {
         self.read_number(3)
     }
 
-    fn parse_type_id(&mut self) -> usize {
+    fn parse_type_id(&mut self) -> Result<usize, PacketError> {
         self.read_number(3)
     }
 
-    fn parse_literal(&mut self) -> usize {
-        let mut literal = 0;
+    fn parse_literal(&mut self) -> Result<BigUint, PacketError> {
+        let mut literal = BigUint::default();
         loop {
-            let prefix = self.next();
-            literal = (literal << 4) | self.read_number(4);
-            if prefix == 0 {
-                return literal;
+            let group = self.read_number(5)?;
+            literal.push_nibble(group as u8);
+            if group & 0x10 == 0 {
+                return Ok(literal);
             }
         }
     }
 }
 
 impl Packet {
-    fn version_sum(&self) -> usize {
+    pub fn version_sum(&self) -> usize {
         self.version
             + match &self.val {
                 Value::Literal(_) => 0,
@@ -100,67 +248,165 @@ impl Packet {
             }
     }
 
-    fn evaluate(&self) -> usize {
-        match &self.val {
-            Value::Literal(n) => *n,
-            Value::Operator(sub_packets) => match self.type_id {
-                0 => sub_packets.iter().map(|p| p.evaluate()).sum(),
-                1 => sub_packets.iter().map(|p| p.evaluate()).product(),
-                2 => sub_packets.iter().map(|p| p.evaluate()).min().unwrap(),
-                3 => sub_packets.iter().map(|p| p.evaluate()).max().unwrap(),
-                5 => {
-                    if sub_packets[0].evaluate() > sub_packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                6 => {
-                    if sub_packets[0].evaluate() < sub_packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                7 => {
-                    if sub_packets[0].evaluate() == sub_packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                _ => panic!("invalid type_id: {}", self.type_id),
-            },
+    /// Checks that an operator packet can combine `count` sub-packets.
+    fn check_operator(&self, count: usize) -> Result<(), PacketError> {
+        match (self.type_id, count) {
+            (0..=3, 1..) | (5..=7, 2) => Ok(()),
+            (type_id @ (0..=3 | 5..=7), count) => Err(PacketError::OperandCount { type_id, count }),
+            (type_id, _) => Err(PacketError::InvalidTypeId { type_id }),
         }
     }
-}
 
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    let input: Vec<u8> = input
-        .trim()
-        .bytes()
-        .map(|hex_digit| {
-            format!(
-                "{:04b}",
-                match hex_digit {
-                    b'0'..=b'9' => hex_digit - b'0',
-                    b'A'..=b'F' => hex_digit - b'A' + 10,
-                    b'a'..=b'f' => hex_digit - b'a' + 10,
-                    _ => panic!("invalid hex digit: {}", hex_digit),
-                }
-            )
-            .bytes()
-            .map(|b| b - b'0')
-            .collect::<Vec<u8>>()
+    /// The packet's value, failing with `PacketError::Overflow` at the
+    /// first packet whose value does not fit in a `usize`.
+    pub fn evaluate(&self) -> Result<usize, PacketError> {
+        self.evaluate_at(&mut Vec::new())
+    }
+
+    fn evaluate_at(&self, path: &mut Vec<usize>) -> Result<usize, PacketError> {
+        let overflow = |path: &[usize]| PacketError::Overflow {
+            path: path.to_vec(),
+        };
+        let sub_packets = match &self.val {
+            Value::Literal(n) => return n.to_usize().ok_or_else(|| overflow(path)),
+            Value::Operator(sub_packets) => sub_packets,
+        };
+        let mut values = Vec::with_capacity(sub_packets.len());
+        for (i, packet) in sub_packets.iter().enumerate() {
+            path.push(i);
+            values.push(packet.evaluate_at(path)?);
+            path.pop();
+        }
+        self.check_operator(values.len())?;
+
+        let value = match self.type_id {
+            0 => values.iter().try_fold(0usize, |sum, &n| sum.checked_add(n)),
+            1 => values
+                .iter()
+                .try_fold(1usize, |product, &n| product.checked_mul(n)),
+            2 => values.iter().copied().min(),
+            3 => values.iter().copied().max(),
+            5 => Some((values[0] > values[1]) as usize),
+            6 => Some((values[0] < values[1]) as usize),
+            7 => Some((values[0] == values[1]) as usize),
+            _ => unreachable!("operator was checked"),
+        };
+        value.ok_or_else(|| overflow(path))
+    }
+
+    /// The packet's value, however large.
+    pub fn evaluate_big(&self) -> Result<BigUint, PacketError> {
+        let sub_packets = match &self.val {
+            Value::Literal(n) => return Ok(n.clone()),
+            Value::Operator(sub_packets) => sub_packets,
+        };
+        let values = sub_packets
+            .iter()
+            .map(Packet::evaluate_big)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.check_operator(values.len())?;
+
+        Ok(match self.type_id {
+            0 => values.iter().fold(BigUint::default(), |sum, n| &sum + n),
+            1 => values
+                .iter()
+                .fold(BigUint::from(1usize), |product, n| &product * n),
+            2 => values.into_iter().min().unwrap(),
+            3 => values.into_iter().max().unwrap(),
+            5 => BigUint::from(values[0] > values[1]),
+            6 => BigUint::from(values[0] < values[1]),
+            7 => BigUint::from(values[0] == values[1]),
+            _ => unreachable!("operator was checked"),
         })
-        .flatten()
+    }
+}
+
+pub type Input = Packet;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    parse_with_spans(input).map(|(packet, _)| packet)
+}
+
+/// The packet along with the span of it and each of its sub-packets, as
+/// given by `Parser::spans`.
+pub fn parse_with_spans(input: &str) -> Result<(Packet, Vec<Span>), ParseError> {
+    let source = Source::new(DAY, input);
+    let transmission = input.trim();
+    source.check_chars(transmission, |c| c.is_ascii_hexdigit(), "invalid hex digit")?;
+
+    let reader = BitReader::from_hex(transmission).expect("hex digits were checked");
+    let mut parser = Parser::new(reader);
+    let packet = parser.parse_packet().map_err(|e| {
+        // Point at the hex digit holding the offending bit.
+        let at = match e.offset().map(|offset| offset / 4) {
+            Some(digit) if digit < transmission.len() => &transmission[digit..digit + 1],
+            _ => &transmission[transmission.len()..],
+        };
+        source.error(at, e.to_string())
+    })?;
+    Ok((packet, parser.into_spans()))
+}
+
+pub fn part1(packet: &Packet) -> usize {
+    packet.version_sum()
+}
+
+pub fn part2(packet: &Packet) -> Result<usize, PacketError> {
+    packet.evaluate()
+}
+
+/// Part 2 without overflow, for transmissions whose value needs more than
+/// a `usize`.
+pub fn value(packet: &Packet) -> Result<BigUint, PacketError> {
+    packet.evaluate_big()
+}
+
+/// A packet nested at most `depth` deep, whose value fits in 40 bits.
+pub fn generate_packet(rng: &mut Rng, depth: usize) -> Packet {
+    let version = rng.below(8);
+
+    if depth == 0 || rng.chance(1, 3) {
+        let groups = rng.between(1, 4) as usize;
+        return Packet {
+            version,
+            type_id: 4,
+            val: Value::Literal(BigUint::from(rng.below(1 << (4 * groups)))),
+        };
+    }
+
+    let type_id = *rng.pick(&[0, 1, 2, 3, 5, 6, 7]);
+    let count = if type_id >= 5 { 2 } else { rng.between(1, 3) };
+    let sub_packets: Vec<Packet> = (0..count)
+        .map(|_| generate_packet(rng, depth - 1))
         .collect();
 
-    let mut parser = Parser::new(input);
-    let packet = parser.parse_packet();
+    let product = sub_packets
+        .iter()
+        .try_fold(1usize, |product, packet| {
+            product.checked_mul(packet.evaluate().ok()?)
+        })
+        .filter(|&product| product < 1 << 40);
+    let type_id = match (type_id, product) {
+        (1, None) => 0,
+        (type_id, _) => type_id,
+    };
 
-    println!("part 1: {}", packet.version_sum());
-    println!("part 2: {}", packet.evaluate());
+    Packet {
+        version,
+        type_id,
+        val: Value::Operator(sub_packets),
+    }
+}
+
+/// A transmission holding one packet nested up to `size / 3 + 1` deep.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let packet = generate_packet(rng, (size / 3 + 1).min(5));
+    let length_type = |_: &Packet| {
+        if rng.chance(1, 2) {
+            LengthType::Bits
+        } else {
+            LengthType::Count
+        }
+    };
+    packet.to_hex(length_type).unwrap() + "\n"
 }
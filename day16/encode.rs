@@ -0,0 +1,137 @@
+use crate::Packet;
+use crate::Value;
+use std::error;
+use std::fmt;
+
+/// How an operator packet gives the size of its sub-packets: length type
+/// ID 0 is their total length in bits, 1 is their number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    Bits,
+    Count,
+}
+
+/// Why a packet cannot be encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// A field does not fit in its bits.
+    TooLarge {
+        field: &'static str,
+        value: usize,
+        bits: usize,
+    },
+    /// A literal with a type ID other than 4, or an operator with type ID 4.
+    TypeMismatch { type_id: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::TooLarge { field, value, bits } => {
+                write!(f, "{} {} does not fit in {} bits", field, value, bits)
+            }
+            EncodeError::TypeMismatch { type_id: 4 } => {
+                write!(f, "operator packet with literal type ID 4")
+            }
+            EncodeError::TypeMismatch { type_id } => {
+                write!(f, "literal packet with operator type ID {}", type_id)
+            }
+        }
+    }
+}
+
+impl error::Error for EncodeError {}
+
+fn push_bits(
+    bits: &mut Vec<u8>,
+    field: &'static str,
+    value: usize,
+    width: usize,
+) -> Result<(), EncodeError> {
+    if value >> width != 0 {
+        return Err(EncodeError::TooLarge {
+            field,
+            value,
+            bits: width,
+        });
+    }
+    bits.extend((0..width).rev().map(|i| (value >> i & 1) as u8));
+    Ok(())
+}
+
+/// `bits` as uppercase hex, padded with zeros to whole bytes.
+fn hex(bits: &[u8]) -> String {
+    let digits = bits.len().div_ceil(8) * 2;
+    (0..digits)
+        .map(|i| {
+            let digit =
+                (4 * i..4 * i + 4).fold(0, |n, bit| n << 1 | *bits.get(bit).unwrap_or(&0) as u32);
+            char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
+impl Packet {
+    fn encode(
+        &self,
+        bits: &mut Vec<u8>,
+        length_type: &mut impl FnMut(&Packet) -> LengthType,
+    ) -> Result<(), EncodeError> {
+        push_bits(bits, "version", self.version, 3)?;
+        push_bits(bits, "type ID", self.type_id, 3)?;
+
+        match &self.val {
+            Value::Literal(n) if self.type_id == 4 => {
+                let groups = n.bits().div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    bits.push((i > 0) as u8);
+                    push_bits(bits, "literal group", n.nibble(i) as usize, 4)?;
+                }
+            }
+            Value::Operator(sub_packets) if self.type_id != 4 => match length_type(self) {
+                LengthType::Bits => {
+                    let mut inner = Vec::new();
+                    for packet in sub_packets {
+                        packet.encode(&mut inner, length_type)?;
+                    }
+                    bits.push(0);
+                    push_bits(bits, "sub-packet length", inner.len(), 15)?;
+                    bits.extend(inner);
+                }
+                LengthType::Count => {
+                    bits.push(1);
+                    push_bits(bits, "sub-packet count", sub_packets.len(), 11)?;
+                    for packet in sub_packets {
+                        packet.encode(bits, length_type)?;
+                    }
+                }
+            },
+            _ => {
+                return Err(EncodeError::TypeMismatch {
+                    type_id: self.type_id,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The packet's bits, one per byte, with `length_type` choosing how
+    /// each operator packet gives the size of its sub-packets.
+    pub fn to_bits(
+        &self,
+        mut length_type: impl FnMut(&Packet) -> LengthType,
+    ) -> Result<Vec<u8>, EncodeError> {
+        let mut bits = Vec::new();
+        self.encode(&mut bits, &mut length_type)?;
+        Ok(bits)
+    }
+
+    /// The packet as a hex transmission, as read by `parse`.
+    pub fn to_hex(
+        &self,
+        length_type: impl FnMut(&Packet) -> LengthType,
+    ) -> Result<String, EncodeError> {
+        Ok(hex(&self.to_bits(length_type)?))
+    }
+}
@@ -0,0 +1,155 @@
+use crate::LengthType;
+use crate::Packet;
+use crate::Span;
+use crate::Value;
+use std::fmt::Write;
+use std::slice;
+
+/// The operator with `type_id` as a function name, for `infix`.
+fn name(type_id: usize) -> String {
+    match type_id {
+        0 => "sum".to_string(),
+        1 => "product".to_string(),
+        2 => "min".to_string(),
+        3 => "max".to_string(),
+        5 => "gt".to_string(),
+        6 => "lt".to_string(),
+        7 => "eq".to_string(),
+        type_id => format!("type{}", type_id),
+    }
+}
+
+/// The operator with `type_id` as the head of an S-expression.
+fn symbol(type_id: usize) -> String {
+    match type_id {
+        0 => "+".to_string(),
+        1 => "*".to_string(),
+        5 => ">".to_string(),
+        6 => "<".to_string(),
+        7 => "=".to_string(),
+        type_id => name(type_id),
+    }
+}
+
+/// The infix operator joining `count` sub-packets of an operator with
+/// `type_id`, and its precedence, if it has one.
+fn operator(type_id: usize, count: usize) -> Option<(&'static str, u8)> {
+    match (type_id, count) {
+        (0, 2..) => Some((" + ", 2)),
+        (1, 2..) => Some((" * ", 3)),
+        (5, 2) => Some((" > ", 1)),
+        (6, 2) => Some((" < ", 1)),
+        (7, 2) => Some((" == ", 1)),
+        _ => None,
+    }
+}
+
+/// Writes `packet` in infix, in parentheses if its operator binds no
+/// tighter than the one it is an operand of. Equal precedence gets them too,
+/// so that the expression keeps the shape of the tree.
+fn write_infix(out: &mut String, packet: &Packet, outer: u8) {
+    let sub_packets = match &packet.val {
+        Value::Literal(n) => {
+            write!(out, "{}", n).unwrap();
+            return;
+        }
+        Value::Operator(sub_packets) => sub_packets,
+    };
+
+    match operator(packet.type_id, sub_packets.len()) {
+        Some((operator, precedence)) => {
+            let parenthesized = precedence <= outer;
+            if parenthesized {
+                out.push('(');
+            }
+            for (i, sub_packet) in sub_packets.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(operator);
+                }
+                write_infix(out, sub_packet, precedence);
+            }
+            if parenthesized {
+                out.push(')');
+            }
+        }
+        None => {
+            out.push_str(&name(packet.type_id));
+            out.push('(');
+            for (i, sub_packet) in sub_packets.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_infix(out, sub_packet, 0);
+            }
+            out.push(')');
+        }
+    }
+}
+
+/// The packet as an expression with infix operators, such as
+/// `(1 + 3) * min(7, 9)`. Sums and products of one sub-packet, and
+/// operators with an unknown type ID, are written as functions.
+pub fn infix(packet: &Packet) -> String {
+    let mut out = String::new();
+    write_infix(&mut out, packet, 0);
+    out
+}
+
+fn write_sexpr(out: &mut String, packet: &Packet) {
+    match &packet.val {
+        Value::Literal(n) => write!(out, "{}", n).unwrap(),
+        Value::Operator(sub_packets) => {
+            write!(out, "({}", symbol(packet.type_id)).unwrap();
+            for sub_packet in sub_packets {
+                out.push(' ');
+                write_sexpr(out, sub_packet);
+            }
+            out.push(')');
+        }
+    }
+}
+
+/// The packet as an S-expression, such as `(* (+ 1 3) (min 7 9))`.
+pub fn sexpr(packet: &Packet) -> String {
+    let mut out = String::new();
+    write_sexpr(&mut out, packet);
+    out
+}
+
+fn write_tree(out: &mut String, packet: &Packet, spans: &mut slice::Iter<Span>, depth: usize) {
+    let span = spans.next();
+    write!(out, "{:1$}", "", 2 * depth).unwrap();
+    if let Some(span) = span {
+        write!(out, "[{}..{}] ", span.bits.start, span.bits.end).unwrap();
+    }
+    write!(out, "version {}, type {}", packet.version, packet.type_id).unwrap();
+
+    match &packet.val {
+        Value::Literal(n) => writeln!(out, ", literal {}", n).unwrap(),
+        Value::Operator(sub_packets) => {
+            write!(out, " ({})", symbol(packet.type_id)).unwrap();
+            match span.and_then(|span| span.length) {
+                Some((LengthType::Bits, bits)) => {
+                    write!(out, ", length type 0 ({} bits)", bits).unwrap()
+                }
+                Some((LengthType::Count, count)) => {
+                    write!(out, ", length type 1 ({} sub-packets)", count).unwrap()
+                }
+                None => {}
+            }
+            out.push('\n');
+            for sub_packet in sub_packets {
+                write_tree(out, sub_packet, spans, depth + 1);
+            }
+        }
+    }
+}
+
+/// The packet as an indented tree, one packet per line, annotated with
+/// the bit offsets and length types in `spans`, as given by
+/// `Parser::spans`. Packets without a span are written without them.
+pub fn tree(packet: &Packet, spans: &[Span]) -> String {
+    let mut out = String::new();
+    write_tree(&mut out, packet, &mut spans.iter(), 0);
+    out
+}
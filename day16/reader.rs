@@ -0,0 +1,78 @@
+use crate::PacketError;
+
+/// Reads a transmission bit by bit, most significant bit first, from bytes
+/// holding eight bits each.
+#[derive(Debug, Clone)]
+pub struct BitReader {
+    bytes: Vec<u8>,
+    len: usize,
+    pos: usize,
+}
+
+impl BitReader {
+    /// Raw binary input, all of whose bits belong to the transmission.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let len = bytes.len() * 8;
+        Self { bytes, len, pos: 0 }
+    }
+
+    /// Four bits per hex digit, or `None` if `hex` holds anything else.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let mut bytes = vec![0; hex.len().div_ceil(2)];
+        for (i, c) in hex.chars().enumerate() {
+            let digit = c.to_digit(16)? as u8;
+            bytes[i / 2] |= digit << (4 - 4 * (i % 2));
+        }
+        Some(Self {
+            bytes,
+            len: hex.len() * 4,
+            pos: 0,
+        })
+    }
+
+    /// One bit per byte, as `Packet::to_bits` gives them.
+    pub fn from_bits(bits: &[u8]) -> Self {
+        let mut bytes = vec![0; bits.len().div_ceil(8)];
+        for (i, &bit) in bits.iter().enumerate() {
+            bytes[i / 8] |= (bit & 1) << (7 - i % 8);
+        }
+        Self {
+            bytes,
+            len: bits.len(),
+            pos: 0,
+        }
+    }
+
+    /// The number of bits in the transmission.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The offset of the next bit to read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The next `count` bits, at most 64, as a number.
+    pub fn read(&mut self, count: usize) -> Result<u64, PacketError> {
+        assert!(count <= 64, "cannot read {} bits at once", count);
+        let end = self.pos + count;
+        if end > self.len {
+            return Err(PacketError::UnexpectedEnd { offset: self.len });
+        }
+
+        let mut value = 0u64;
+        while self.pos < end {
+            let used = self.pos % 8;
+            let take = (8 - used).min(end - self.pos);
+            let byte = self.bytes[self.pos / 8] as u64;
+            value = (value << take) | ((byte >> (8 - used - take)) & ((1 << take) - 1));
+            self.pos += take;
+        }
+        Ok(value)
+    }
+}
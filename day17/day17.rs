@@ -1,14 +1,81 @@
-fn main() {
-    let x0 = 169;
-    let x1 = 206;
-    let y0 = -108;
-    let y1 = -68;
+use common::ParseError;
+use common::Rng;
+use common::Source;
+use std::str::FromStr;
+
+const DAY: u32 = 17;
+
+pub struct Target {
+    pub x0: isize,
+    pub x1: isize,
+    pub y0: isize,
+    pub y1: isize,
+}
+
+fn parse_range(source: &Source, s: &str, axis: &str) -> Result<(isize, isize), ParseError> {
+    let (start, end) = s
+        .strip_prefix(axis)
+        .and_then(|s| s.strip_prefix('='))
+        .and_then(|s| s.split_once(".."))
+        .ok_or_else(|| source.error(s, format!("expected `{}=<start>..<end>`", axis)))?;
+
+    let start = source.parse(start, "range start")?;
+    let end = source.parse(end, "range end")?;
+    if start > end {
+        return Err(source.error(s, "range start is after its end"));
+    }
+    Ok((start, end))
+}
+
+impl FromStr for Target {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = Source::new(DAY, s);
+        let (x_range, y_range) = s
+            .trim()
+            .split_once(',')
+            .ok_or_else(|| source.error(s.trim(), "expected `x=..., y=...`"))?;
+
+        let (x0, x1) = parse_range(&source, x_range.trim(), "x")?;
+        let (y0, y1) = parse_range(&source, y_range.trim(), "y")?;
+        if x0 <= 0 {
+            return Err(source.error(x_range.trim(), "target must be ahead of the probe"));
+        }
+        if y1 >= 0 {
+            return Err(source.error(y_range.trim(), "target must be below the probe"));
+        }
+
+        Ok(Target { x0, x1, y0, y1 })
+    }
+}
+
+pub type Input = Target;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let ranges = input
+        .trim()
+        .strip_prefix("target area: ")
+        .ok_or_else(|| source.error(input.trim(), "expected `target area: x=..., y=...`"))?;
+
+    source.parse_part(ranges)
+}
+
+/// The highest the probe can go while still hitting `target`, and the
+/// number of initial velocities that hit it.
+///
+/// The target is ahead of and below the probe, so faster than `x1` ahead or
+/// `y0` down overshoots at once, and a probe launched upwards at `dy` comes
+/// back down through `y = 0` at `-dy - 1`, overshooting past `-y0 - 1`.
+pub fn launch(target: &Target) -> (isize, usize) {
+    let Target { x0, x1, y0, y1 } = *target;
 
     let mut max = 0;
 
     let mut velocities = Vec::new();
 
-    for dy in -800..800 {
+    for dy in y0..-y0 {
         for dx in 1..=x1 {
             let mut _dx: isize = dx;
             let mut _dy: isize = dy;
@@ -42,6 +109,23 @@ fn main() {
         }
     }
 
-    println!("part 1: {}", max);
-    println!("part 2: {}", velocities.len());
+    (max, velocities.len())
+}
+
+pub fn part1(target: &Target) -> isize {
+    launch(target).0
+}
+
+pub fn part2(target: &Target) -> usize {
+    launch(target).1
+}
+
+/// A target area ahead of and below the probe, further away as `size` grows.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let reach = 20 + 10 * size.min(40) as i64;
+    let x0 = rng.between(10, reach);
+    let x1 = x0 + rng.between(5, 30);
+    let y1 = -rng.between(5, reach / 2 + 5);
+    let y0 = y1 - rng.between(5, 30);
+    format!("target area: x={}..{}, y={}..{}\n", x0, x1, y0, y1)
 }
@@ -1,189 +1,296 @@
-use std::collections::HashMap;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::io;
-
-fn change_orientation(p: (isize, isize, isize), orientation: usize) -> (isize, isize, isize) {
-    let (x, y, z) = p;
-    match orientation {
-        0 => (x, y, z),
-        1 => (-y, x, z),
-        2 => (-x, -y, z),
-        3 => (y, x, z),
-
-        4 => (-x, y, -z),
-        5 => (-y, -x, -z),
-        6 => (x, -y, -z),
-        7 => (y, x, -z),
-
-        8 => (-z, y, x),
-        9 => (-y, -z, x),
-        10 => (z, -z, x),
-        11 => (y, z, x),
-
-        12 => (z, y, -x),
-        13 => (-y, z, -x),
-        14 => (-z, -y, -x),
-        15 => (y, -z, -x),
-
-        16 => (x, -z, y),
-        17 => (z, x, y),
-        18 => (-x, z, y),
-        19 => (-z, -x, y),
-
-        20 => (x, z, -y),
-        21 => (-z, x, -y),
-        22 => (-x, -z, -y),
-        23 => (z, -x, -y),
-
-        _ => panic!("invalid orientation"),
-    }
-}
 
-fn intersection_path_to(
-    scanner: usize,
-    to: usize,
-    intersections: &HashMap<usize, HashMap<usize, ((isize, isize, isize), usize)>>,
-) -> Option<Vec<usize>> {
-    let mut q = VecDeque::new();
-    q.push_back((scanner, Vec::new(), HashSet::new()));
-
-    while let Some((s, path, visited)) = q.pop_front() {
-        if s == to {
-            return Some(path);
-        }
+pub mod export;
+mod fingerprint;
+mod rotation;
 
-        for (&next, _) in intersections.get(&s).unwrap_or(&HashMap::new()) {
-            if !visited.contains(&next) {
-                let mut p = path.clone();
-                let mut v = visited.clone();
-                p.push(next);
-                v.insert(next);
-                q.push_back((next, p, v));
-            }
-        }
-    }
+pub use fingerprint::Fingerprint;
+pub use rotation::Rotation;
 
-    None
-}
+const DAY: u32 = 19;
+
+/// The number of beacons two scanners must both see to be aligned.
+const MIN_OVERLAP: usize = 12;
+
+pub type Point = (isize, isize, isize);
 
-fn main() {
+pub type Input = Vec<HashSet<(isize, isize, isize)>>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
     let mut reports: Vec<HashSet<(isize, isize, isize)>> = Vec::new();
+    let mut lines = input.lines();
 
-    loop {
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        if input.len() == 0 {
-            break;
+    while let Some(header) = lines.next() {
+        let number = header
+            .strip_prefix("--- scanner ")
+            .and_then(|header| header.strip_suffix(" ---"))
+            .ok_or_else(|| source.error(header, "expected `--- scanner <n> ---`"))?;
+        if source.parse::<usize>(number, "scanner number")? != reports.len() {
+            return Err(source.error(number, format!("expected scanner {}", reports.len())));
         }
 
         let mut beacons = HashSet::new();
 
-        loop {
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            if input.len() <= 1 {
-                reports.push(beacons);
+        for line in lines.by_ref() {
+            if line.is_empty() {
                 break;
             }
 
-            let coords: Vec<&str> = input.trim().split(',').collect();
-            assert_eq!(coords.len(), 3);
-            let x = coords[0].parse().unwrap();
-            let y = coords[1].parse().unwrap();
-            let z = coords[2].parse().unwrap();
+            let coords: Vec<&str> = line.trim().split(',').collect();
+            if coords.len() != 3 {
+                return Err(source.error(line, "expected `x,y,z`"));
+            }
+            let x = source.parse(coords[0], "x coordinate")?;
+            let y = source.parse(coords[1], "y coordinate")?;
+            let z = source.parse(coords[2], "z coordinate")?;
 
             beacons.insert((x, y, z));
         }
+
+        if beacons.is_empty() {
+            return Err(source.error(header, "scanner reports no beacons"));
+        }
+        reports.push(beacons);
+    }
+
+    if reports.is_empty() {
+        return Err(source.error(source.end(), "expected at least one scanner report"));
+    }
+    Ok(reports)
+}
+
+/// Where a scanner is and which way it faces, relative to the origin of its
+/// cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub scanner: usize,
+    pub position: Point,
+    /// Takes the scanner's own coordinates into the origin's.
+    pub rotation: Rotation,
+}
+
+/// Scanners linked to each other by overlapping beacons, with everything
+/// given in the coordinates of the lowest numbered one, the origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    pub origin: usize,
+    /// The placement of every scanner in the cluster, in scanner order.
+    pub scanners: Vec<Placement>,
+    pub beacons: HashSet<Point>,
+}
+
+impl Cluster {
+    /// The largest Manhattan distance between two scanners of the cluster.
+    pub fn largest_distance(&self) -> usize {
+        let mut max_distance = 0;
+        for (i, scanner1) in self.scanners.iter().enumerate() {
+            for scanner2 in &self.scanners[i + 1..] {
+                let (p1, p2) = (scanner1.position, scanner2.position);
+                max_distance = max_distance
+                    .max(p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1) + p1.2.abs_diff(p2.2));
+            }
+        }
+        max_distance
     }
+}
 
-    let mut inter = HashMap::new();
-    inter
-        .entry(0)
-        .or_insert(HashMap::new())
-        .insert(0, ((0, 0, 0), 0));
+/// Aligns every pair of scanners seeing at least `min_overlap` of the same
+/// beacons. For each scanner, lists the scanners it is linked to, with the
+/// translation and rotation taking their coordinates into its own.
+fn links(reports: &[HashSet<Point>], min_overlap: usize) -> Vec<Vec<(usize, Point, Rotation)>> {
+    let rotations = Rotation::all();
+    let fingerprints: Vec<Fingerprint> = reports.iter().map(Fingerprint::new).collect();
+    let mut links = vec![Vec::new(); reports.len()];
 
     for scanner1 in 0..reports.len() {
-        for scanner2 in 0..reports.len() {
-            if scanner1 == scanner2 {
-                continue;
+        for scanner2 in (scanner1 + 1)..reports.len() {
+            let (fingerprint1, fingerprint2) = (&fingerprints[scanner1], &fingerprints[scanner2]);
+            if let Some((delta, rotation)) =
+                fingerprint1.align(fingerprint2, &rotations, min_overlap)
+            {
+                let inverse = rotation.inverse();
+                let (x, y, z) = inverse.apply(delta);
+                links[scanner1].push((scanner2, delta, rotation));
+                links[scanner2].push((scanner1, (-x, -y, -z), inverse));
             }
-            for orientation in 0..24 {
-                let mut cnt = HashMap::new();
-
-                for &beacon1 in &reports[scanner1] {
-                    for &beacon2 in &reports[scanner2] {
-                        let beacon2 = change_orientation(beacon2, orientation);
-                        let delta = (
-                            beacon1.0 - beacon2.0,
-                            beacon1.1 - beacon2.1,
-                            beacon1.2 - beacon2.2,
-                        );
-                        *cnt.entry(delta).or_insert(0) += 1;
-                    }
-                }
+        }
+    }
+
+    links
+}
+
+/// Groups the scanners into clusters linked by at least `min_overlap` shared
+/// beacons, ordered by origin. Scanners that cannot be linked to any other
+/// form clusters of their own.
+///
+/// Each cluster is a spanning tree grown breadth first from its origin, so
+/// every scanner's placement is composed once, from its parent's.
+pub fn clusters(reports: &[HashSet<Point>], min_overlap: usize) -> Vec<Cluster> {
+    let links = links(reports, min_overlap);
+    let mut placed: Vec<Option<Placement>> = vec![None; reports.len()];
+    let mut clusters = Vec::new();
+
+    for origin in 0..reports.len() {
+        if placed[origin].is_some() {
+            continue;
+        }
 
-                for (delta, n) in cnt {
-                    if n >= 12 {
-                        inter
-                            .entry(scanner2)
-                            .or_insert(HashMap::new())
-                            .insert(scanner1, (delta, orientation));
-                    }
+        let mut cluster = Cluster {
+            origin,
+            scanners: Vec::new(),
+            beacons: HashSet::new(),
+        };
+        placed[origin] = Some(Placement {
+            scanner: origin,
+            position: (0, 0, 0),
+            rotation: Rotation::IDENTITY,
+        });
+        let mut q = VecDeque::from([origin]);
+
+        while let Some(scanner) = q.pop_front() {
+            let placement = placed[scanner].unwrap();
+            let (position, rotation) = (placement.position, placement.rotation);
+
+            cluster.scanners.push(placement);
+            cluster
+                .beacons
+                .extend(reports[scanner].iter().map(|&beacon| {
+                    let (x, y, z) = rotation.apply(beacon);
+                    (x + position.0, y + position.1, z + position.2)
+                }));
+
+            for &(next, delta, link) in &links[scanner] {
+                if placed[next].is_none() {
+                    let (x, y, z) = rotation.apply(delta);
+                    placed[next] = Some(Placement {
+                        scanner: next,
+                        position: (x + position.0, y + position.1, z + position.2),
+                        rotation: link.then(&rotation),
+                    });
+                    q.push_back(next);
                 }
             }
         }
+
+        cluster.scanners.sort_by_key(|placement| placement.scanner);
+        clusters.push(cluster);
     }
 
-    let mut world = reports[0].clone();
-    let mut scanner_delta_from_0 = Vec::new();
-    scanner_delta_from_0.resize(reports.len(), (0, 0, 0));
-
-    for scanner in 1..reports.len() {
-        if let Some(inter_path) = intersection_path_to(scanner, 0, &inter) {
-            let mut beacons: Vec<(isize, isize, isize)> =
-                reports[scanner].iter().copied().collect();
-            let mut scanner_delta = (0, 0, 0);
-            let mut prev = scanner;
-
-            for next in inter_path {
-                let &(delta, orientation) = inter.get(&prev).unwrap().get(&next).unwrap();
-                scanner_delta = change_orientation(scanner_delta, orientation);
-                scanner_delta = (
-                    scanner_delta.0 + delta.0,
-                    scanner_delta.1 + delta.1,
-                    scanner_delta.2 + delta.2,
-                );
-                scanner_delta_from_0[scanner] = scanner_delta;
+    clusters
+}
+
+/// The cluster of all scanners, or every cluster if some scanner cannot be
+/// linked to scanner 0.
+fn connected(reports: &[HashSet<Point>]) -> Result<Cluster, Vec<Cluster>> {
+    let mut clusters = clusters(reports, MIN_OVERLAP);
+    if clusters.len() > 1 {
+        return Err(clusters);
+    }
+    Ok(clusters.remove(0))
+}
+
+/// Every beacon and every scanner position, relative to scanner 0, or every
+/// cluster if some scanner cannot be linked to scanner 0.
+pub fn align(reports: &[HashSet<Point>]) -> Result<(HashSet<Point>, Vec<Point>), Vec<Cluster>> {
+    let cluster = connected(reports)?;
+    let positions = cluster.scanners.iter().map(|p| p.position).collect();
+    Ok((cluster.beacons, positions))
+}
+
+/// The number of beacons, or every cluster like `align`.
+pub fn part1(reports: &[HashSet<Point>]) -> Result<usize, Vec<Cluster>> {
+    Ok(connected(reports)?.beacons.len())
+}
+
+/// The largest Manhattan distance between two scanners, or every cluster
+/// like `align`.
+pub fn part2(reports: &[HashSet<Point>]) -> Result<usize, Vec<Cluster>> {
+    Ok(connected(reports)?.largest_distance())
+}
+
+fn in_range(scanner: Point, beacon: Point) -> bool {
+    scanner.0.abs_diff(beacon.0) <= 1000
+        && scanner.1.abs_diff(beacon.1) <= 1000
+        && scanner.2.abs_diff(beacon.2) <= 1000
+}
 
-                for b in beacons.iter_mut() {
-                    *b = change_orientation(*b, orientation);
-                    *b = (b.0 + delta.0, b.1 + delta.1, b.2 + delta.2);
+/// Generates reports from `size` scanners, each sharing at least 12 beacons
+/// with an earlier one, along with the ground truth as returned by `align`:
+/// every beacon and every scanner position, relative to scanner 0.
+pub fn generate_scan(rng: &mut Rng, size: usize) -> (String, HashSet<Point>, Vec<Point>) {
+    let mut beacons = HashSet::new();
+    let mut scanners = vec![(0, 0, 0)];
+    let near = |rng: &mut Rng, a: isize, b: isize| {
+        rng.between(a.max(b) as i64 - 1000, a.min(b) as i64 + 1000) as isize
+    };
+
+    for i in 0..size.max(2) {
+        if i > 0 {
+            let parent: Point = scanners[rng.below(i)];
+            let scanner = (
+                parent.0 + rng.between(-1100, 1100) as isize,
+                parent.1 + rng.between(-1100, 1100) as isize,
+                parent.2 + rng.between(-1100, 1100) as isize,
+            );
+
+            let mut shared = 0;
+            while shared < 12 {
+                let beacon = (
+                    near(rng, parent.0, scanner.0),
+                    near(rng, parent.1, scanner.1),
+                    near(rng, parent.2, scanner.2),
+                );
+                if beacons.insert(beacon) {
+                    shared += 1;
                 }
-                prev = next;
             }
+            scanners.push(scanner);
+        }
 
-            for b in beacons {
-                world.insert(b);
-            }
-        } else {
-            panic!("no intersection path from {} to {}", scanner, 0);
+        let scanner = scanners[i];
+        for _ in 0..rng.between(0, 8) {
+            beacons.insert((
+                near(rng, scanner.0, scanner.0),
+                near(rng, scanner.1, scanner.1),
+                near(rng, scanner.2, scanner.2),
+            ));
         }
     }
 
-    println!("part 1: {}", world.len());
+    let rotations = Rotation::all();
+    let mut out = String::new();
 
-    let mut max_distance = 0;
-    for scanner1 in 0..reports.len() {
-        for scanner2 in (scanner1 + 1)..reports.len() {
-            let scanner1_pos = scanner_delta_from_0[scanner1];
-            let scanner2_pos = scanner_delta_from_0[scanner2];
-            max_distance = max_distance.max(
-                scanner1_pos.0.abs_diff(scanner2_pos.0)
-                    + scanner1_pos.1.abs_diff(scanner2_pos.1)
-                    + scanner1_pos.2.abs_diff(scanner2_pos.2),
-            );
+    for (i, &scanner) in scanners.iter().enumerate() {
+        let rotation = if i == 0 {
+            rotations[0]
+        } else {
+            *rng.pick(&rotations)
+        };
+        let mut report: Vec<Point> = beacons
+            .iter()
+            .filter(|&&beacon| in_range(scanner, beacon))
+            .map(|b| rotation.apply((b.0 - scanner.0, b.1 - scanner.1, b.2 - scanner.2)))
+            .collect();
+        report.sort();
+        rng.shuffle(&mut report);
+
+        if i > 0 {
+            out.push('\n');
+        }
+        out += &format!("--- scanner {} ---\n", i);
+        for (x, y, z) in report {
+            out += &format!("{},{},{}\n", x, y, z);
         }
     }
-    println!("part 2: {}", max_distance);
+
+    (out, beacons, scanners)
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    generate_scan(rng, size).0
 }
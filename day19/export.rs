@@ -0,0 +1,133 @@
+use crate::Cluster;
+use crate::Point;
+use std::fmt::Write;
+
+fn sorted_beacons(cluster: &Cluster) -> Vec<Point> {
+    let mut beacons: Vec<Point> = cluster.beacons.iter().copied().collect();
+    beacons.sort();
+    beacons
+}
+
+/// One row per scanner: its cluster's origin, its number, its position and
+/// its rotation matrix, row by row.
+pub fn scanners_csv(clusters: &[Cluster]) -> String {
+    let mut out = String::from("cluster,scanner,x,y,z,r11,r12,r13,r21,r22,r23,r31,r32,r33\n");
+    for cluster in clusters {
+        for placement in &cluster.scanners {
+            let (x, y, z) = placement.position;
+            write!(
+                out,
+                "{},{},{},{},{}",
+                cluster.origin, placement.scanner, x, y, z
+            )
+            .unwrap();
+            for value in placement.rotation.matrix().iter().flatten() {
+                write!(out, ",{}", value).unwrap();
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// One row per distinct beacon, with its cluster's origin.
+pub fn beacons_csv(clusters: &[Cluster]) -> String {
+    let mut out = String::from("cluster,x,y,z\n");
+    for cluster in clusters {
+        for (x, y, z) in sorted_beacons(cluster) {
+            writeln!(out, "{},{},{},{}", cluster.origin, x, y, z).unwrap();
+        }
+    }
+    out
+}
+
+/// Every cluster with its scanners and beacons, e.g.
+/// `{"clusters":[{"origin":0,"scanners":[{"scanner":0,"position":[0,0,0],
+/// "rotation":[[1,0,0],[0,1,0],[0,0,1]]}],"beacons":[[1,2,3]]}]}`.
+pub fn json(clusters: &[Cluster]) -> String {
+    let point = |(x, y, z): Point| format!("[{},{},{}]", x, y, z);
+
+    let clusters: Vec<String> = clusters
+        .iter()
+        .map(|cluster| {
+            let scanners: Vec<String> = cluster
+                .scanners
+                .iter()
+                .map(|placement| {
+                    let rows: Vec<String> = placement
+                        .rotation
+                        .matrix()
+                        .iter()
+                        .map(|&[a, b, c]| point((a, b, c)))
+                        .collect();
+                    format!(
+                        "{{\"scanner\":{},\"position\":{},\"rotation\":[{}]}}",
+                        placement.scanner,
+                        point(placement.position),
+                        rows.join(",")
+                    )
+                })
+                .collect();
+            let beacons: Vec<String> = sorted_beacons(cluster).into_iter().map(point).collect();
+
+            format!(
+                "{{\"origin\":{},\"scanners\":[{}],\"beacons\":[{}]}}",
+                cluster.origin,
+                scanners.join(","),
+                beacons.join(",")
+            )
+        })
+        .collect();
+
+    format!("{{\"clusters\":[{}]}}\n", clusters.join(","))
+}
+
+/// An ASCII PLY point cloud of a cluster, with its beacons in white and its
+/// scanners in red.
+pub fn ply(cluster: &Cluster) -> String {
+    let beacons = sorted_beacons(cluster);
+    let mut out = String::new();
+
+    writeln!(out, "ply\nformat ascii 1.0").unwrap();
+    writeln!(out, "comment day 19 cluster at scanner {}", cluster.origin).unwrap();
+    writeln!(
+        out,
+        "element vertex {}",
+        beacons.len() + cluster.scanners.len()
+    )
+    .unwrap();
+    for property in [
+        "float x",
+        "float y",
+        "float z",
+        "uchar red",
+        "uchar green",
+        "uchar blue",
+    ] {
+        writeln!(out, "property {}", property).unwrap();
+    }
+    writeln!(out, "end_header").unwrap();
+
+    for (x, y, z) in beacons {
+        writeln!(out, "{} {} {} 255 255 255", x, y, z).unwrap();
+    }
+    for placement in &cluster.scanners {
+        let (x, y, z) = placement.position;
+        writeln!(out, "{} {} {} 255 0 0", x, y, z).unwrap();
+    }
+    out
+}
+
+/// Every export, as `(file name, contents)`: the CSV files, the JSON file and
+/// a PLY file for each cluster.
+pub fn files(clusters: &[Cluster]) -> Vec<(String, String)> {
+    let mut files = vec![
+        ("scanners.csv".to_string(), scanners_csv(clusters)),
+        ("beacons.csv".to_string(), beacons_csv(clusters)),
+        ("reconstruction.json".to_string(), json(clusters)),
+    ];
+    for cluster in clusters {
+        files.push((format!("cluster{}.ply", cluster.origin), ply(cluster)));
+    }
+    files
+}
@@ -0,0 +1,148 @@
+use crate::Point;
+use crate::Rotation;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+fn offset(from: Point, to: Point) -> Point {
+    (to.0 - from.0, to.1 - from.1, to.2 - from.2)
+}
+
+/// The distances between every pair of a scanner's beacons, as sorted
+/// absolute coordinate differences. These are the same whichever way the
+/// scanner is facing, so two scanners seeing the same `n` beacons share at
+/// least `n * (n - 1) / 2` of them.
+pub struct Fingerprint<'a> {
+    report: &'a HashSet<Point>,
+    beacons: Vec<Point>,
+    /// Each distance with the indices in `beacons` of the pair it separates,
+    /// sorted by distance.
+    distances: Vec<([usize; 3], usize, usize)>,
+}
+
+impl<'a> Fingerprint<'a> {
+    pub fn new(report: &'a HashSet<Point>) -> Self {
+        let beacons: Vec<Point> = report.iter().copied().collect();
+        let mut distances = Vec::with_capacity(beacons.len() * beacons.len().saturating_sub(1) / 2);
+
+        for (i, a) in beacons.iter().enumerate() {
+            for (j, b) in beacons.iter().enumerate().skip(i + 1) {
+                let mut distance = [a.0.abs_diff(b.0), a.1.abs_diff(b.1), a.2.abs_diff(b.2)];
+                distance.sort_unstable();
+                distances.push((distance, i, j));
+            }
+        }
+        distances.sort_unstable();
+
+        Self {
+            report,
+            beacons,
+            distances,
+        }
+    }
+
+    /// Calls `f` with the beacon pairs of both fingerprints that are the same
+    /// distance apart.
+    fn common(
+        &self,
+        other: &Fingerprint,
+        mut f: impl FnMut((usize, usize), (usize, usize)) -> bool,
+    ) {
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.distances.len() && j < other.distances.len() {
+            let distance = self.distances[i].0;
+            match distance.cmp(&other.distances[j].0) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    let i_end = i + self.distances[i..]
+                        .iter()
+                        .take_while(|d| d.0 == distance)
+                        .count();
+                    let j_end = j + other.distances[j..]
+                        .iter()
+                        .take_while(|d| d.0 == distance)
+                        .count();
+
+                    for &(_, a, b) in &self.distances[i..i_end] {
+                        for &(_, c, d) in &other.distances[j..j_end] {
+                            if !f((a, b), (c, d)) {
+                                return;
+                            }
+                        }
+                    }
+                    (i, j) = (i_end, j_end);
+                }
+            }
+        }
+    }
+
+    /// The number of distances found in both fingerprints, counting repeats.
+    pub fn shared(&self, other: &Fingerprint) -> usize {
+        let mut shared = 0;
+        self.common(other, |_, _| {
+            shared += 1;
+            true
+        });
+        shared
+    }
+
+    /// The translation and rotation taking `other`'s coordinates into this
+    /// scanner's, if at least `min_overlap` of their beacons line up.
+    ///
+    /// Every pair of equally distant beacon pairs votes for the transforms
+    /// that would line them up, and only a transform with enough votes is
+    /// checked against the beacons themselves.
+    pub fn align(
+        &self,
+        other: &Fingerprint,
+        rotations: &[Rotation],
+        min_overlap: usize,
+    ) -> Option<(Point, Rotation)> {
+        let needed = min_overlap * min_overlap.saturating_sub(1) / 2;
+        let mut votes = HashMap::new();
+        let mut found = None;
+
+        self.common(other, |(a, b), (c, d)| {
+            let (a, b) = (self.beacons[a], self.beacons[b]);
+            let (c, d) = (other.beacons[c], other.beacons[d]);
+            let expected = offset(a, b);
+
+            for &rotation in rotations {
+                let rotated = rotation.apply(offset(c, d));
+                // The pairs may be listed in opposite orders.
+                let from = if rotated == expected {
+                    c
+                } else if rotated == (-expected.0, -expected.1, -expected.2) {
+                    d
+                } else {
+                    continue;
+                };
+                let delta = offset(rotation.apply(from), a);
+
+                let n = votes.entry((rotation, delta)).or_insert(0);
+                *n += 1;
+                if *n == needed.max(1) && self.lined_up(other, rotation, delta) >= min_overlap {
+                    found = Some((delta, rotation));
+                    return false;
+                }
+            }
+            true
+        });
+
+        found
+    }
+
+    fn lined_up(&self, other: &Fingerprint, rotation: Rotation, delta: Point) -> usize {
+        other
+            .beacons
+            .iter()
+            .map(|&beacon| rotation.apply(beacon))
+            .filter(|b| {
+                self.report
+                    .contains(&(b.0 + delta.0, b.1 + delta.1, b.2 + delta.2))
+            })
+            .count()
+    }
+}
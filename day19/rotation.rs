@@ -0,0 +1,83 @@
+use crate::Point;
+use std::array;
+
+/// A rotation of space by quarter turns about the axes, as an integer
+/// matrix. Only proper rotations can be built, so a scanner is never
+/// mirrored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    matrix: [[isize; 3]; 3],
+}
+
+/// A quarter turn about the x axis, taking y to z.
+const QUARTER_X: Rotation = Rotation {
+    matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+};
+
+/// A quarter turn about the y axis, taking z to x.
+const QUARTER_Y: Rotation = Rotation {
+    matrix: [[0, 0, 1], [0, 1, 0], [-1, 0, 0]],
+};
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// Every orientation a scanner can have: the group generated by quarter
+    /// turns about x and y, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        let mut rotations = vec![Rotation::IDENTITY];
+        let mut i = 0;
+
+        while i < rotations.len() {
+            for quarter in [QUARTER_X, QUARTER_Y] {
+                let rotation = rotations[i].then(&quarter);
+                if !rotations.contains(&rotation) {
+                    rotations.push(rotation);
+                }
+            }
+            i += 1;
+        }
+
+        rotations
+    }
+
+    pub fn matrix(&self) -> [[isize; 3]; 3] {
+        self.matrix
+    }
+
+    pub fn apply(&self, (x, y, z): Point) -> Point {
+        let m = &self.matrix;
+        (
+            m[0][0] * x + m[0][1] * y + m[0][2] * z,
+            m[1][0] * x + m[1][1] * y + m[1][2] * z,
+            m[2][0] * x + m[2][1] * y + m[2][2] * z,
+        )
+    }
+
+    /// The rotation applying `self`, then `next`.
+    pub fn then(&self, next: &Rotation) -> Rotation {
+        let matrix = array::from_fn(|row| {
+            array::from_fn(|col| {
+                (0..3)
+                    .map(|k| next.matrix[row][k] * self.matrix[k][col])
+                    .sum()
+            })
+        });
+        Rotation { matrix }
+    }
+
+    /// The rotation undoing `self`; for a rotation matrix, its transpose.
+    pub fn inverse(&self) -> Rotation {
+        let matrix = array::from_fn(|row| array::from_fn(|col| self.matrix[col][row]));
+        Rotation { matrix }
+    }
+
+    pub fn determinant(&self) -> isize {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
@@ -1,74 +1,113 @@
-use std::collections::HashMap;
-use std::io;
-
-type Image = HashMap<(isize, isize), bool>;
-
-fn enhance(image: &Image, algorithm: &[u8], step: usize) -> Image {
-    let min_y = *image.keys().map(|(y, _)| y).min().unwrap();
-    let min_x = *image.keys().map(|(_, x)| x).min().unwrap();
-    let max_y = *image.keys().map(|(y, _)| y).max().unwrap();
-    let max_x = *image.keys().map(|(_, x)| x).max().unwrap();
-
-    let mut enhanced = HashMap::new();
-
-    for y in (min_y - 1)..=(max_y + 1) {
-        for x in (min_x - 1)..=(max_x + 1) {
-            let mut t = 0;
-
-            for dy in -1..=1 {
-                for dx in -1..=1 {
-                    let ny = y + dy;
-                    let nx = x + dx;
-                    t *= 2;
-
-                    if let Some(&v) = image.get(&(ny, nx)) {
-                        t += if v { 1 } else { 0 };
-                    } else if algorithm[0] == b'#' {
-                        t += step % 2;
-                    }
-                }
-            }
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 
-            enhanced.insert((y, x), algorithm[t] == b'#');
-        }
-    }
+const DAY: u32 = 20;
+
+pub type Image = Grid<bool>;
 
-    enhanced
+/// Whether the infinite background around the image is lit after `steps`
+/// enhancements.
+fn background(algorithm: &[u8], steps: usize) -> bool {
+    match (algorithm[0], algorithm[511]) {
+        (b'#', b'#') => steps > 0,
+        (b'#', _) => steps % 2 == 1,
+        _ => false,
+    }
 }
 
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-
-    let algo = input.trim().as_bytes().to_vec();
-    assert_eq!(algo.len(), 512);
-
-    let mut image: HashMap<(isize, isize), bool> = HashMap::new();
-    let mut line = 0;
-
-    loop {
-        input.clear();
-        match io::stdin().read_line(&mut input) {
-            Ok(0) => break,
-            Ok(1) => continue,
-            Ok(_) => {
-                for (column, c) in input.trim().bytes().enumerate() {
-                    image.insert((line, column as isize), c == b'#');
-                }
+pub fn enhance(image: &Image, algorithm: &[u8], step: usize) -> Image {
+    let background = background(algorithm, step);
+
+    Grid::from_fn(image.width() + 2, image.height() + 2, |x, y| {
+        let mut t = 0;
+
+        for dy in -2..=0 {
+            for dx in -2..=0 {
+                t *= 2;
+
+                let lit = match image.offset(x, y, dx, dy) {
+                    Some(p) => image[p],
+                    None => background,
+                };
+                t += if lit { 1 } else { 0 };
             }
-            Err(e) => panic!("{}", e),
         }
 
-        line += 1;
+        algorithm[t] == b'#'
+    })
+}
+
+pub struct Input {
+    pub algorithm: Vec<u8>,
+    pub image: Image,
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let (algo, image) = input.split_once('\n').unwrap_or((input, source.end()));
+    let algo = algo.trim();
+    if algo.is_empty() {
+        return Err(source.error(algo, "expected image enhancement algorithm"));
+    }
+    source.check_chars(algo, |c| c == '#' || c == '.', "expected `#` or `.`")?;
+    if algo.len() != 512 {
+        return Err(source.error(algo, "image enhancement algorithm must be 512 characters"));
     }
 
-    for step in 0..2 {
-        image = enhance(&image, &algo, step);
+    let image = Grid::parse(
+        &source,
+        image.trim_start(),
+        |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        },
+        "expected `#` or `.`",
+    )?;
+
+    Ok(Input {
+        algorithm: algo.as_bytes().to_vec(),
+        image,
+    })
+}
+
+/// The number of lit pixels after `steps` enhancements, or `None` if the
+/// background is lit and infinitely many are.
+pub fn lit_after(input: &Input, steps: usize) -> Option<usize> {
+    if background(&input.algorithm, steps) {
+        return None;
     }
-    println!("part 1: {}", image.values().filter(|&&v| v).count());
 
-    for step in 2..50 {
-        image = enhance(&image, &algo, step);
+    let mut image = input.image.clone();
+
+    for step in 0..steps {
+        image = enhance(&image, &input.algorithm, step);
     }
-    println!("part 2: {}", image.values().filter(|&&v| v).count());
+
+    Some(image.iter().filter(|&&v| v).count())
+}
+
+pub fn part1(input: &Input) -> Option<usize> {
+    lit_after(input, 2)
+}
+
+pub fn part2(input: &Input) -> Option<usize> {
+    lit_after(input, 50)
+}
+
+/// A random algorithm, never lighting every pixel of the infinite image for
+/// good, and a `size` by `size` input image.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let pixel = |rng: &mut Rng| if rng.chance(1, 2) { '#' } else { '.' };
+
+    let mut algorithm: Vec<char> = (0..512).map(|_| pixel(rng)).collect();
+    if algorithm[0] == '#' {
+        algorithm[511] = '.';
+    }
+
+    let size = size.max(1);
+    let image = Grid::from_fn(size, size, |_, _| pixel(rng));
+    format!("{}\n\n{}\n", algorithm.iter().collect::<String>(), image)
 }
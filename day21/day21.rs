@@ -1,50 +1,60 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::collections::HashMap;
-use std::io;
-
-fn read_player_starting_position() -> usize {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
 
+const DAY: u32 = 21;
+fn parse_player_starting_position(
+    source: &Source,
+    input: &str,
+    player: usize,
+) -> Result<usize, ParseError> {
+    let prefix = format!("Player {} starting position: ", player);
     let pos = input
         .trim()
-        .chars()
-        .rev()
-        .take_while(|c| c.is_ascii_digit())
-        .fold(0, |acc, c| acc * 10 + c.to_digit(10).unwrap());
-    if pos == 0 {
-        panic!("error reading player starting position");
+        .strip_prefix(&prefix)
+        .ok_or_else(|| source.error(input, format!("expected `{}<position>`", prefix)))?;
+
+    match source.parse(pos, "starting position")? {
+        pos @ 1..=10 => Ok(pos),
+        _ => Err(source.error(pos, "starting position must be between 1 and 10")),
     }
-    pos as usize
 }
 
-struct Dice {
+pub struct Dice {
     next_value: usize,
 }
 
 impl Dice {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self { next_value: 1 }
     }
 
-    fn roll(&mut self) -> usize {
+    pub fn roll(&mut self) -> usize {
         let r = self.next_value;
         self.next_value = if r == 100 { 1 } else { r + 1 };
         r
     }
 }
 
+impl Default for Dice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Player {
-    position: usize,
-    score: usize,
+pub struct Player {
+    pub position: usize,
+    pub score: usize,
 }
 
 impl Player {
-    fn new(position: usize) -> Self {
+    pub fn new(position: usize) -> Self {
         Self { position, score: 0 }
     }
 
-    fn turn(&mut self, steps: usize) {
+    pub fn turn(&mut self, steps: usize) {
         for _ in 0..steps {
             self.position += 1;
             if self.position > 10 {
@@ -56,24 +66,27 @@ impl Player {
     }
 }
 
-fn count_wins(
+/// The number of universes each player wins in, or `None` if there are too
+/// many to count in a `usize`.
+pub fn count_wins(
     player1: Player,
     player2: Player,
     player1_turn: bool,
+    win_score: usize,
     mem: &mut HashMap<(Player, Player, bool), (usize, usize)>,
-) -> (usize, usize) {
+) -> Option<(usize, usize)> {
     if let Some(&n) = mem.get(&(player1, player2, player1_turn)) {
-        return n;
+        return Some(n);
     }
-    if player1.score >= 21 {
+    if player1.score >= win_score {
         mem.insert((player1, player2, true), (1, 0));
         mem.insert((player1, player2, false), (1, 0));
-        return (1, 0);
+        return Some((1, 0));
     }
-    if player2.score >= 21 {
+    if player2.score >= win_score {
         mem.insert((player1, player2, true), (0, 1));
         mem.insert((player1, player2, false), (0, 1));
-        return (0, 1);
+        return Some((0, 1));
     }
 
     let mut wins = (0, 0);
@@ -85,34 +98,51 @@ fn count_wins(
                 let (a, b) = if player1_turn {
                     let mut p1 = player1;
                     p1.turn(roll);
-                    count_wins(p1, player2, false, mem)
+                    count_wins(p1, player2, false, win_score, mem)?
                 } else {
                     let mut p2 = player2;
                     p2.turn(roll);
-                    count_wins(player1, p2, true, mem)
+                    count_wins(player1, p2, true, win_score, mem)?
                 };
-                wins.0 += a;
-                wins.1 += b;
+                wins.0 = a.checked_add(wins.0)?;
+                wins.1 = b.checked_add(wins.1)?;
             }
         }
     }
 
     mem.insert((player1, player2, player1_turn), wins);
 
-    wins
+    Some(wins)
+}
+
+pub struct Input {
+    pub player1: usize,
+    pub player2: usize,
 }
 
-fn main() {
-    let player1_initial_pos = read_player_starting_position();
-    let player2_initial_pos = read_player_starting_position();
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut lines = input.lines();
+    let mut next_position = |player| {
+        let line = lines
+            .next()
+            .ok_or_else(|| source.error(source.end(), "expected player starting position"))?;
+        parse_player_starting_position(&source, line, player)
+    };
+
+    let player1 = next_position(1)?;
+    let player2 = next_position(2)?;
+    Ok(Input { player1, player2 })
+}
 
-    let mut player1 = Player::new(player1_initial_pos);
-    let mut player2 = Player::new(player2_initial_pos);
+pub fn practice_game(input: &Input, win_score: usize) -> usize {
+    let mut player1 = Player::new(input.player1);
+    let mut player2 = Player::new(input.player2);
     let mut dice = Dice::new();
     let mut player1_turn = true;
     let mut n = 0;
 
-    while player1.score < 1000 && player2.score < 1000 {
+    while player1.score < win_score && player2.score < win_score {
         let r = dice.roll() + dice.roll() + dice.roll();
         n += 3;
 
@@ -125,13 +155,33 @@ fn main() {
         player1_turn = !player1_turn;
     }
 
-    println!("part 1: {}", player1.score.min(player2.score) * n);
+    player1.score.min(player2.score) * n
+}
 
+pub fn dirac_game(input: &Input, win_score: usize) -> Option<usize> {
     let wins = count_wins(
-        Player::new(player1_initial_pos),
-        Player::new(player2_initial_pos),
+        Player::new(input.player1),
+        Player::new(input.player2),
         true,
+        win_score,
         &mut HashMap::new(),
-    );
-    println!("part 2: {}", wins.0.max(wins.1));
+    )?;
+    Some(wins.0.max(wins.1))
+}
+
+pub fn part1(input: &Input) -> usize {
+    practice_game(input, 1000)
+}
+
+pub fn part2(input: &Input) -> Option<usize> {
+    dirac_game(input, 21)
+}
+
+/// Random starting positions; `size` is ignored.
+pub fn generate(rng: &mut Rng, _size: usize) -> String {
+    format!(
+        "Player 1 starting position: {}\nPlayer 2 starting position: {}\n",
+        rng.between(1, 10),
+        rng.between(1, 10)
+    )
 }
@@ -1,50 +1,69 @@
-use std::io;
-use std::io::BufRead;
-use std::num::ParseIntError;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::str::FromStr;
 
+const DAY: u32 = 22;
+
 #[derive(Clone, Debug)]
-struct Range {
-    start: isize,
-    end: isize,
+pub struct Range {
+    pub start: isize,
+    pub end: isize,
 }
 
 impl FromStr for Range {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nums: Vec<&str> = s[2..].split("..").collect();
-        assert_eq!(nums.len(), 2);
-        Ok(Self {
-            start: nums[0].parse::<isize>()?,
-            end: nums[1].parse::<isize>()?,
-        })
+        let source = Source::new(DAY, s);
+        let (start, end) = s
+            .split_once('=')
+            .and_then(|(_, range)| range.split_once(".."))
+            .ok_or_else(|| source.error(s, "expected `<axis>=<start>..<end>`"))?;
+
+        let range = Self {
+            start: source.parse(start, "range start")?,
+            end: source.parse(end, "range end")?,
+        };
+        if range.start > range.end {
+            return Err(source.error(s, "range start is after its end"));
+        }
+        Ok(range)
     }
 }
 
 #[derive(Clone, Debug)]
-struct Cuboid {
-    x_range: Range,
-    y_range: Range,
-    z_range: Range,
+pub struct Cuboid {
+    pub x_range: Range,
+    pub y_range: Range,
+    pub z_range: Range,
 }
 
 impl FromStr for Cuboid {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = Source::new(DAY, s);
         let ranges: Vec<&str> = s.split(',').collect();
-        assert_eq!(ranges.len(), 3);
+        if ranges.len() != 3 {
+            return Err(source.error(s, "expected `x=...,y=...,z=...`"));
+        }
+        for (range, axis) in ranges.iter().zip(["x=", "y=", "z="]) {
+            if !range.starts_with(axis) {
+                return Err(source.error(range, format!("expected `{}` range", axis)));
+            }
+        }
+
         Ok(Self {
-            x_range: ranges[0].parse::<Range>()?,
-            y_range: ranges[1].parse::<Range>()?,
-            z_range: ranges[2].parse::<Range>()?,
+            x_range: source.parse_part(ranges[0])?,
+            y_range: source.parse_part(ranges[1])?,
+            z_range: source.parse_part(ranges[2])?,
         })
     }
 }
 
 impl Cuboid {
-    fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
         for (rs, ro) in [&self.x_range, &self.y_range, &self.z_range].iter().zip([
             &other.x_range,
             &other.y_range,
@@ -71,7 +90,7 @@ impl Cuboid {
         })
     }
 
-    fn volume(&self) -> isize {
+    pub fn volume(&self) -> isize {
         (self.x_range.end - self.x_range.start + 1)
             * (self.y_range.end - self.y_range.start + 1)
             * (self.z_range.end - self.z_range.start + 1)
@@ -79,41 +98,40 @@ impl Cuboid {
 }
 
 #[derive(Debug)]
-struct RebootStep {
-    on: bool,
-    cuboid: Cuboid,
+pub struct RebootStep {
+    pub on: bool,
+    pub cuboid: Cuboid,
 }
 
 impl FromStr for RebootStep {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(' ').collect();
-        assert_eq!(parts.len(), 2);
+        let source = Source::new(DAY, s);
+        let (state, cuboid) = s
+            .split_once(' ')
+            .ok_or_else(|| source.error(s, "expected `on|off <cuboid>`"))?;
 
-        let on = match parts[0] {
+        let on = match state {
             "on" => true,
             "off" => false,
-            _ => panic!("error parsing reboot step"),
+            _ => return Err(source.error(state, "expected `on` or `off`")),
         };
 
-        let cuboid = parts[1].trim().parse::<Cuboid>()?;
+        let cuboid = source.parse_part(cuboid.trim())?;
 
         Ok(Self { on, cuboid })
     }
 }
 
-fn main() {
-    let steps: Vec<RebootStep> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| {
-            line.unwrap()
-                .parse::<RebootStep>()
-                .expect("error parsing reboot step")
-        })
-        .collect();
+pub type Input = Vec<RebootStep>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input.lines().map(|line| source.parse_part(line)).collect()
+}
 
+pub fn reboot(steps: &[RebootStep]) -> Vec<(Cuboid, bool)> {
     let mut reactor: Vec<(Cuboid, bool)> = Vec::new();
 
     for step in steps {
@@ -132,42 +150,77 @@ fn main() {
             }
         }
         if step.on {
-            new_reactor.push((step.cuboid, step.on));
+            new_reactor.push((step.cuboid.clone(), step.on));
         }
         reactor = new_reactor;
     }
 
+    reactor
+}
+
+pub fn cubes_within(steps: &[RebootStep], region: isize) -> isize {
+    let reactor = reboot(steps);
+
     let region = Cuboid {
         x_range: Range {
-            start: -50,
-            end: 50,
+            start: -region,
+            end: region,
         },
         y_range: Range {
-            start: -50,
-            end: 50,
+            start: -region,
+            end: region,
         },
         z_range: Range {
-            start: -50,
-            end: 50,
+            start: -region,
+            end: region,
         },
     };
 
-    println!(
-        "part 1: {}",
-        reactor
-            .iter()
-            .map(|(cuboid, on)| {
-                cuboid
-                    .intersection(&region)
-                    .map_or(0, |inter| inter.volume() * if *on { 1 } else { -1 })
-            })
-            .sum::<isize>()
-    );
-    println!(
-        "part 2: {}",
-        reactor
-            .iter()
-            .map(|(cuboid, on)| { cuboid.volume() * if *on { 1 } else { -1 } })
-            .sum::<isize>()
-    );
+    reactor
+        .iter()
+        .map(|(cuboid, on)| {
+            cuboid
+                .intersection(&region)
+                .map_or(0, |inter| inter.volume() * if *on { 1 } else { -1 })
+        })
+        .sum()
+}
+
+pub fn part1(steps: &[RebootStep]) -> isize {
+    cubes_within(steps, 50)
+}
+
+pub fn part2(steps: &[RebootStep]) -> isize {
+    reboot(steps)
+        .iter()
+        .map(|(cuboid, on)| cuboid.volume() * if *on { 1 } else { -1 })
+        .sum()
+}
+
+/// `size` reboot steps, about half of them inside the initialization region.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|i| {
+            let (extent, span) = if rng.chance(1, 2) {
+                (50, 30)
+            } else {
+                (100_000, 30_000)
+            };
+            let mut range = || {
+                let start = rng.between(-extent, extent);
+                (start, (start + rng.between(0, span)).min(extent))
+            };
+            let ((x0, x1), (y0, y1), (z0, z1)) = (range(), range(), range());
+
+            let state = if i == 0 || rng.chance(2, 3) {
+                "on"
+            } else {
+                "off"
+            };
+            format!(
+                "{} x={}..{},y={}..{},z={}..{}\n",
+                state, x0, x1, y0, y1, z0, z1
+            )
+        })
+        .collect()
 }
@@ -1,6 +1,10 @@
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
-use std::collections::HashMap;
+use common::astar;
+use common::ParseError;
+use common::Rng;
+use common::SearchProblem;
+use common::Source;
+
+const DAY: u32 = 23;
 
 #[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
 struct Room {
@@ -108,56 +112,37 @@ fn path_clear(a: usize, b: usize, corridor: [char; 11]) -> bool {
     corridor[from..=to].iter().all(|&c| c == '.')
 }
 
-fn min_energy(rooms: [&str; 4]) -> usize {
-    let mut q = BinaryHeap::new();
+/// The amphipods' moves from their starting burrow until they are all in
+/// their rooms.
+struct Burrow {
+    start: State,
+}
 
-    let room_size = rooms[0].len();
-    let mut initial_state = State {
-        corridor: ['.'; 11],
-        rooms: [
-            Room::new(0, room_size),
-            Room::new(1, room_size),
-            Room::new(2, room_size),
-            Room::new(3, room_size),
-        ],
-    };
+impl SearchProblem for Burrow {
+    type State = State;
 
-    for (room, s) in initial_state.rooms.iter_mut().zip(rooms) {
-        for (room_space, amphipod) in room.contents.iter_mut().zip(s.chars()) {
-            *room_space = amphipod;
-        }
+    fn start(&self) -> State {
+        self.start
     }
 
-    q.push(Reverse((0, initial_state.clone())));
-
-    let mut seen = HashMap::new();
-    seen.insert(initial_state, 0);
-
-    while let Some(Reverse((energy, state))) = q.pop() {
+    fn successors(&self, state: &State, next: &mut Vec<(State, usize)>) {
         let corridor = state.corridor;
         let rooms = state.rooms;
 
-        if rooms.iter().all(|room| room.ok()) {
-            return energy;
-        }
-
         for room in rooms {
             if let Some(rp) = room.first_amphipod() {
                 for cp in [0, 1, 3, 5, 7, 9, 10] {
                     let rcp = room.corridor_position();
                     if path_clear(rcp, cp, corridor) {
                         let steps = cp.abs_diff(rcp) + rp + 1;
-                        let mut corridor = corridor.clone();
+                        let mut corridor = corridor;
                         corridor[cp] = room.contents[rp];
-                        let mut rooms = rooms.clone();
+                        let mut rooms = rooms;
                         rooms[room.index].contents[rp] = '.';
-                        let next_state = State { corridor, rooms };
-                        let next_energy = energy + energy_required(steps, corridor[cp]);
-
-                        if next_energy < *seen.get(&next_state).unwrap_or(&usize::MAX) {
-                            q.push(Reverse((next_energy, next_state.clone())));
-                            seen.insert(next_state, next_energy);
-                        }
+                        next.push((
+                            State { corridor, rooms },
+                            energy_required(steps, corridor[cp]),
+                        ));
                     }
                 }
             }
@@ -171,27 +156,180 @@ fn min_energy(rooms: [&str; 4]) -> usize {
                 let x = if cp < rcp { cp + 1 } else { cp - 1 };
                 if path_clear(rcp, x, corridor) {
                     let steps = cp.abs_diff(rcp) + rp + 1;
-                    let mut rooms = rooms.clone();
+                    let mut rooms = rooms;
                     rooms[dest_room.index].contents[rp] = c;
-                    let mut corridor = corridor.clone();
+                    let mut corridor = corridor;
                     corridor[cp] = '.';
-                    let next_state = State { corridor, rooms };
-                    let next_energy =
-                        energy + energy_required(steps, rooms[dest_room.index].contents[rp]);
-
-                    if next_energy < *seen.get(&next_state).unwrap_or(&usize::MAX) {
-                        q.push(Reverse((next_energy, next_state.clone())));
-                        seen.insert(next_state, next_energy);
-                    }
+                    next.push((State { corridor, rooms }, energy_required(steps, c)));
                 }
             }
         }
     }
 
-    unreachable!()
+    fn is_goal(&self, state: &State) -> bool {
+        state.rooms.iter().all(|room| room.ok())
+    }
+
+    /// The energy for every amphipod not yet settled to reach the doorway of
+    /// its room and step in, ignoring everything in its way.
+    fn heuristic(&self, state: &State) -> usize {
+        let to_doorway = |position: usize, amphipod: char| {
+            position.abs_diff(state.rooms[amphipod_dest_room(amphipod)].corridor_position())
+        };
+
+        let in_corridor: usize = state
+            .corridor
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c != '.')
+            .map(|(cp, &c)| energy_required(to_doorway(cp, c) + 1, c))
+            .sum();
+
+        let in_rooms: usize = state
+            .rooms
+            .iter()
+            .flat_map(|room| {
+                let contents = &room.contents[..room.size];
+                let rcp = room.corridor_position();
+                contents.iter().enumerate().filter_map(move |(rp, &c)| {
+                    let settled = contents[rp..].iter().all(|&c| c == room.amphipod());
+                    // Leaving and re-entering the same room takes at least two
+                    // steps along the corridor.
+                    (c != '.' && !settled)
+                        .then(|| energy_required(rp + 1 + to_doorway(rcp, c).max(2) + 1, c))
+                })
+            })
+            .sum();
+
+        in_corridor + in_rooms
+    }
 }
 
-fn main() {
-    println!("part 1: {}", min_energy(["CD", "AC", "BA", "DB"]));
-    println!("part 2: {}", min_energy(["CDDD", "ACBC", "BBAA", "DACB"]));
+/// The least energy needed to organize the amphipods, or `None` if they
+/// cannot be organized.
+pub fn min_energy(rooms: [&str; 4]) -> Option<usize> {
+    let room_size = rooms[0].len();
+    if room_size > 4
+        || rooms.iter().any(|room| room.len() != room_size)
+        || "ABCD".chars().any(|amphipod| {
+            rooms
+                .iter()
+                .flat_map(|room| room.chars())
+                .filter(|&c| c == amphipod)
+                .count()
+                != room_size
+        })
+    {
+        return None;
+    }
+    let mut start = State {
+        corridor: ['.'; 11],
+        rooms: [
+            Room::new(0, room_size),
+            Room::new(1, room_size),
+            Room::new(2, room_size),
+            Room::new(3, room_size),
+        ],
+    };
+
+    for (room, s) in start.rooms.iter_mut().zip(rooms) {
+        for (room_space, amphipod) in room.contents.iter_mut().zip(s.chars()) {
+            *room_space = amphipod;
+        }
+    }
+
+    astar(&Burrow { start }).map(|path| path.cost)
+}
+
+pub type Input = [String; 4];
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let mut rooms: [String; 4] = Default::default();
+
+    for line in input.lines().skip(2) {
+        source.check_chars(
+            line,
+            |c| " #ABCD".contains(c),
+            "expected an amphipod or a wall",
+        )?;
+        let amphipods: Vec<char> = line.chars().filter(|c| c.is_ascii_uppercase()).collect();
+        if amphipods.is_empty() {
+            break;
+        }
+        if amphipods.len() != 4 {
+            return Err(source.error(line, "expected 4 amphipods"));
+        }
+        if rooms[0].len() == 4 {
+            return Err(source.error(line, "rooms hold at most 4 amphipods"));
+        }
+
+        for (room, amphipod) in rooms.iter_mut().zip(amphipods) {
+            room.push(amphipod);
+        }
+    }
+
+    if rooms[0].is_empty() {
+        return Err(source.error(source.end(), "expected rows of amphipods"));
+    }
+    Ok(rooms)
+}
+
+/// Parses rooms listed left to right as comma-separated amphipods from the
+/// top of each room to its bottom, e.g. `BA,CD,BC,DA`.
+pub fn parse_rooms(s: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, s);
+    let mut rooms: [String; 4] = Default::default();
+    let mut parts = s.trim().split(',');
+
+    for room in &mut rooms {
+        let part = parts
+            .next()
+            .ok_or_else(|| source.error(source.end(), "expected 4 rooms"))?
+            .trim();
+        source.check_chars(part, |c| "ABCD".contains(c), "expected an amphipod")?;
+        if part.is_empty() || part.len() > 4 {
+            return Err(source.error(part, "rooms hold between 1 and 4 amphipods"));
+        }
+        *room = part.to_string();
+    }
+    if let Some(part) = parts.next() {
+        return Err(source.error(part, "expected 4 rooms"));
+    }
+
+    Ok(rooms)
+}
+
+/// Inserts `rows` below the top amphipod of each room.
+pub fn unfold(rooms: &Input, rows: &Input) -> Input {
+    let mut rooms = rooms.clone();
+    for (room, folded) in rooms.iter_mut().zip(rows) {
+        room.insert_str(1, folded);
+    }
+    rooms
+}
+
+pub fn organize(rooms: &Input) -> Option<usize> {
+    min_energy([&rooms[0], &rooms[1], &rooms[2], &rooms[3]])
+}
+
+pub fn part1(rooms: &Input) -> Option<usize> {
+    organize(rooms)
+}
+
+pub fn part2(rooms: &Input) -> Option<usize> {
+    let folded = ["DD", "CB", "BA", "AC"].map(String::from);
+    organize(&unfold(rooms, &folded))
+}
+
+/// A random arrangement of two amphipods of each type; `size` is ignored.
+pub fn generate(rng: &mut Rng, _size: usize) -> String {
+    let mut amphipods: Vec<char> = "AABBCCDD".chars().collect();
+    rng.shuffle(&mut amphipods);
+    let a = &amphipods;
+
+    format!(
+        "#############\n#...........#\n###{}#{}#{}#{}###\n  #{}#{}#{}#{}#\n  #########\n",
+        a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7]
+    )
 }
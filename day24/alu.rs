@@ -0,0 +1,139 @@
+use crate::Argument;
+use crate::Instruction;
+use crate::Variable;
+use std::error;
+use std::fmt;
+
+/// The ALU's four registers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub w: i64,
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Registers {
+    pub fn get(&self, variable: Variable) -> i64 {
+        match variable {
+            Variable::W => self.w,
+            Variable::X => self.x,
+            Variable::Y => self.y,
+            Variable::Z => self.z,
+        }
+    }
+
+    pub fn set(&mut self, variable: Variable, value: i64) {
+        match variable {
+            Variable::W => self.w = value,
+            Variable::X => self.x = value,
+            Variable::Y => self.y = value,
+            Variable::Z => self.z = value,
+        }
+    }
+
+    fn value(&self, argument: Argument) -> i64 {
+        match argument {
+            Argument::Variable(variable) => self.get(variable),
+            Argument::Immediate(value) => value,
+        }
+    }
+}
+
+/// A program crash, with the index of the instruction that caused it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluError {
+    DivisionByZero {
+        at: usize,
+    },
+    /// `mod a b` with `a < 0` or `b < 0`.
+    NegativeModulo {
+        at: usize,
+    },
+    /// `inp` with the input already used up.
+    MissingInput {
+        at: usize,
+    },
+}
+
+impl fmt::Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AluError::DivisionByZero { at } => write!(f, "instruction {}: division by zero", at),
+            AluError::NegativeModulo { at } => write!(f, "instruction {}: negative modulo", at),
+            AluError::MissingInput { at } => write!(f, "instruction {}: no input left", at),
+        }
+    }
+}
+
+impl error::Error for AluError {}
+
+/// Runs `program` from all-zero registers, reading `inp` values from
+/// `input`, and returns the final registers.
+pub fn run(
+    program: &[Instruction],
+    input: impl IntoIterator<Item = i64>,
+) -> Result<Registers, AluError> {
+    run_from(Registers::default(), program, input)
+}
+
+/// Like `run`, starting from `registers`.
+pub fn run_from(
+    mut registers: Registers,
+    program: &[Instruction],
+    input: impl IntoIterator<Item = i64>,
+) -> Result<Registers, AluError> {
+    let mut input = input.into_iter();
+
+    for (at, &instruction) in program.iter().enumerate() {
+        let (a, value) = match instruction {
+            Instruction::Inp(a) => (a, input.next().ok_or(AluError::MissingInput { at })?),
+            Instruction::Add(a, b) => (a, registers.get(a).wrapping_add(registers.value(b))),
+            Instruction::Mul(a, b) => (a, registers.get(a).wrapping_mul(registers.value(b))),
+            Instruction::Div(a, b) => {
+                let b = registers.value(b);
+                if b == 0 {
+                    return Err(AluError::DivisionByZero { at });
+                }
+                (a, registers.get(a).wrapping_div(b))
+            }
+            Instruction::Mod(a, b) => {
+                let (a_value, b) = (registers.get(a), registers.value(b));
+                if b == 0 {
+                    return Err(AluError::DivisionByZero { at });
+                }
+                if a_value < 0 || b < 0 {
+                    return Err(AluError::NegativeModulo { at });
+                }
+                (a, a_value % b)
+            }
+            Instruction::Eql(a, b) => (a, (registers.get(a) == registers.value(b)) as i64),
+        };
+        registers.set(a, value);
+    }
+
+    Ok(registers)
+}
+
+/// Whether MONAD `program` accepts `model_number`, fed to it digit by digit:
+/// it must have no zero digits and leave `z` at 0.
+pub fn accepts(program: &[Instruction], model_number: i64) -> Result<bool, AluError> {
+    match digits(model_number) {
+        Some(digits) => Ok(run(program, digits)?.z == 0),
+        None => Ok(false),
+    }
+}
+
+/// The digits of `model_number`, or `None` if it is not a valid model
+/// number: not positive, or with a zero digit.
+pub(crate) fn digits(model_number: i64) -> Option<Vec<i64>> {
+    if model_number <= 0 {
+        return None;
+    }
+    let digits: Vec<i64> = model_number
+        .to_string()
+        .bytes()
+        .map(|digit| (digit - b'0') as i64)
+        .collect();
+    (!digits.contains(&0)).then_some(digits)
+}
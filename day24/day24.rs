@@ -1,23 +1,37 @@
+use common::ParseError;
+use common::Rng;
+use common::Source;
 use std::cmp::Ordering;
 use std::fmt;
-use std::io;
-use std::io::BufRead;
-use std::num::ParseIntError;
+use std::ops::Range;
 use std::str::FromStr;
 
-enum Variable {
+pub mod alu;
+pub mod compile;
+mod decompile;
+mod interval;
+pub mod search;
+
+pub use decompile::decompile;
+
+const DAY: u32 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variable {
     W,
     X,
     Y,
     Z,
 }
 
-enum Argument {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Argument {
     Variable(Variable),
     Immediate(i64),
 }
 
-enum Instruction {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
     Inp(Variable),
     Add(Variable, Argument),
     Mul(Variable, Argument),
@@ -26,32 +40,20 @@ enum Instruction {
     Eql(Variable, Argument),
 }
 
-enum InstructionErrorKind {
-    Mnemonic,
-    Variable,
-    Immediate(ParseIntError),
-    MissingArgs,
-    TooManyArgs,
-}
-
-struct ParseInstructionError {
-    kind: InstructionErrorKind,
-}
-
-impl fmt::Debug for ParseInstructionError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.kind {
-            InstructionErrorKind::Mnemonic => write!(f, "invalid mnemonic"),
-            InstructionErrorKind::Variable => write!(f, "invalid variable"),
-            InstructionErrorKind::Immediate(e) => write!(f, "invalid immediate value: {}", e),
-            InstructionErrorKind::MissingArgs => write!(f, "missing arguments"),
-            InstructionErrorKind::TooManyArgs => write!(f, "too many arguments"),
+impl Variable {
+    /// The register's position in `w`, `x`, `y`, `z` order.
+    fn index(self) -> usize {
+        match self {
+            Variable::W => 0,
+            Variable::X => 1,
+            Variable::Y => 2,
+            Variable::Z => 3,
         }
     }
 }
 
 impl FromStr for Variable {
-    type Err = ParseInstructionError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -59,72 +61,94 @@ impl FromStr for Variable {
             "x" => Ok(Variable::X),
             "y" => Ok(Variable::Y),
             "z" => Ok(Variable::Z),
-            _ => Err(ParseInstructionError {
-                kind: InstructionErrorKind::Variable,
-            }),
+            _ => Err(Source::new(DAY, s).error(s, "invalid variable")),
         }
     }
 }
 
 impl FromStr for Argument {
-    type Err = ParseInstructionError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.parse::<Variable>() {
             Ok(v) => Ok(Argument::Variable(v)),
-            Err(_) => match s.parse::<i64>() {
-                Ok(val) => Ok(Argument::Immediate(val)),
-                Err(e) => Err(ParseInstructionError {
-                    kind: InstructionErrorKind::Immediate(e),
-                }),
-            },
+            Err(_) => Source::new(DAY, s)
+                .parse(s, "immediate value")
+                .map(Argument::Immediate),
         }
     }
 }
 
 impl FromStr for Instruction {
-    type Err = ParseInstructionError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = Source::new(DAY, s);
         let tokens: Vec<&str> = s.split(' ').collect();
         match tokens[0] {
             "inp" => match tokens.len().cmp(&2) {
-                Ordering::Less => Err(ParseInstructionError {
-                    kind: InstructionErrorKind::MissingArgs,
-                }),
-                Ordering::Equal => Ok(Instruction::Inp(tokens[1].parse()?)),
-                Ordering::Greater => Err(ParseInstructionError {
-                    kind: InstructionErrorKind::TooManyArgs,
-                }),
+                Ordering::Less => Err(source.error(source.end(), "missing arguments")),
+                Ordering::Equal => Ok(Instruction::Inp(source.parse_part(tokens[1])?)),
+                Ordering::Greater => Err(source.error(tokens[2], "too many arguments")),
             },
 
             "add" | "mul" | "div" | "mod" | "eql" => match tokens.len().cmp(&3) {
-                Ordering::Less => Err(ParseInstructionError {
-                    kind: InstructionErrorKind::MissingArgs,
-                }),
-                Ordering::Equal => match tokens[0] {
-                    "add" => Ok(Instruction::Add(tokens[1].parse()?, tokens[2].parse()?)),
-                    "mul" => Ok(Instruction::Mul(tokens[1].parse()?, tokens[2].parse()?)),
-                    "div" => Ok(Instruction::Div(tokens[1].parse()?, tokens[2].parse()?)),
-                    "mod" => Ok(Instruction::Mod(tokens[1].parse()?, tokens[2].parse()?)),
-                    "eql" => Ok(Instruction::Eql(tokens[1].parse()?, tokens[2].parse()?)),
-                    _ => unreachable!(),
-                },
-                Ordering::Greater => Err(ParseInstructionError {
-                    kind: InstructionErrorKind::TooManyArgs,
-                }),
+                Ordering::Less => Err(source.error(source.end(), "missing arguments")),
+                Ordering::Equal => {
+                    let a = source.parse_part(tokens[1])?;
+                    let b = source.parse_part(tokens[2])?;
+                    match tokens[0] {
+                        "add" => Ok(Instruction::Add(a, b)),
+                        "mul" => Ok(Instruction::Mul(a, b)),
+                        "div" => Ok(Instruction::Div(a, b)),
+                        "mod" => Ok(Instruction::Mod(a, b)),
+                        "eql" => Ok(Instruction::Eql(a, b)),
+                        _ => unreachable!(),
+                    }
+                }
+                Ordering::Greater => Err(source.error(tokens[3], "too many arguments")),
             },
 
-            _ => {
-                return Err(ParseInstructionError {
-                    kind: InstructionErrorKind::Mnemonic,
-                })
-            }
+            _ => Err(source.error(tokens[0], "invalid mnemonic")),
+        }
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Variable::W => "w",
+            Variable::X => "x",
+            Variable::Y => "y",
+            Variable::Z => "z",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Argument::Variable(v) => write!(f, "{}", v),
+            Argument::Immediate(value) => write!(f, "{}", value),
         }
     }
 }
 
-fn solve(vals: &[(i64, i64, i64)]) -> (i64, i64) {
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Inp(a) => write!(f, "inp {}", a),
+            Instruction::Add(a, b) => write!(f, "add {} {}", a, b),
+            Instruction::Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Instruction::Div(a, b) => write!(f, "div {} {}", a, b),
+            Instruction::Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Instruction::Eql(a, b) => write!(f, "eql {} {}", a, b),
+        }
+    }
+}
+
+pub fn solve(vals: &[(i64, i64, i64)]) -> (i64, i64) {
     let mut stack = Vec::new();
     let mut min_val = [0; 14];
     let mut max_val = [0; 14];
@@ -153,50 +177,140 @@ fn solve(vals: &[(i64, i64, i64)]) -> (i64, i64) {
     )
 }
 
-fn main() {
-    let program: Vec<Instruction> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| {
-            line.expect("error reading input")
-                .parse()
-                .expect("error parsing input")
-        })
+pub type Input = Vec<Instruction>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    input.lines().map(|line| source.parse_part(line)).collect()
+}
+
+/// The `(a, b, c)` parameters of each block of a MONAD program following the
+/// usual 18-instruction template, where `c` is the value added to the digit
+/// before pushing it and `b` the value added to the popped one before the
+/// comparison. `None` if the program does not follow it.
+pub fn block_parameters(program: &[Instruction]) -> Option<Vec<(i64, i64, i64)>> {
+    if program.len() != 14 * 18 {
+        return None;
+    }
+
+    program.chunks(18).map(block_template).collect()
+}
+
+/// The `(a, b, c)` parameters of one block following the MONAD template.
+fn block_template(block: &[Instruction]) -> Option<(i64, i64, i64)> {
+    let immediate = |i: usize| match block.get(i)? {
+        Instruction::Div(_, Argument::Immediate(value))
+        | Instruction::Add(_, Argument::Immediate(value)) => Some(*value),
+        _ => None,
+    };
+    let (a, b, c) = (immediate(4)?, immediate(5)?, immediate(15)?);
+
+    let expected = parse(&generate_block(a, b, c)).unwrap();
+    (block == expected.as_slice()).then_some((a, b, c))
+}
+
+/// The instructions from each `inp` up to the next one, or the end.
+fn input_blocks(program: &[Instruction]) -> Vec<Range<usize>> {
+    let inputs: Vec<usize> = (0..program.len())
+        .filter(|&i| matches!(program[i], Instruction::Inp(_)))
         .collect();
 
-    assert_eq!(program.len() % 18, 0);
+    inputs
+        .iter()
+        .zip(inputs.iter().skip(1).chain([&program.len()]))
+        .map(|(&start, &end)| start..end)
+        .collect()
+}
 
-    let mut vals: Vec<(i64, i64, i64)> = Vec::new();
+/// Whether `solve` applies to these block parameters: every block either
+/// always pushes its digit or pops one, they pair up like brackets, and
+/// every pair of digits can be matched.
+fn solvable(vals: &[(i64, i64, i64)]) -> bool {
+    let mut stack = Vec::new();
 
-    for p_start in (0..program.len()).step_by(18) {
-        let a = match &program[p_start + 4] {
-            Instruction::Div(_, arg) => match arg {
-                Argument::Immediate(val) => val,
-                _ => unreachable!(),
+    for &(a, b, c) in vals {
+        match a {
+            1 if b > 9 => stack.push(c),
+            26 => match stack.pop() {
+                Some(c) if (b + c).abs() <= 8 => {}
+                _ => return false,
             },
-            _ => unreachable!(),
-        };
-        let b = match &program[p_start + 5] {
-            Instruction::Add(_, arg) => match arg {
-                Argument::Immediate(val) => val,
-                _ => unreachable!(),
-            },
-            _ => unreachable!(),
-        };
-        let c = match &program[p_start + 15] {
-            Instruction::Add(_, arg) => match arg {
-                Argument::Immediate(val) => val,
-                _ => unreachable!(),
-            },
-            _ => unreachable!(),
-        };
-
-        vals.push((*a, *b, *c));
+            _ => return false,
+        }
     }
 
-    assert_eq!(vals.len(), 14);
+    stack.is_empty()
+}
 
+/// The smallest and largest model numbers, if `program` follows the MONAD
+/// template closely enough for `solve`, checked by running the compiled
+/// program on both.
+fn paired(program: &[Instruction]) -> Option<(i64, i64)> {
+    let vals = block_parameters(program).filter(|vals| solvable(vals))?;
     let (min, max) = solve(&vals);
-    println!("part 1: {}", max);
-    println!("part 2: {}", min);
+    let compiled = compile::compile(program, 1..=9);
+    compiled
+        .accepts_all(&[min, max])
+        .iter()
+        .all(|accepted| accepted == &Ok(true))
+        .then_some((min, max))
+}
+
+pub fn largest(program: &[Instruction]) -> Option<i64> {
+    match paired(program) {
+        Some((_, max)) => Some(max),
+        None => search::first_accepted(program, [9, 8, 7, 6, 5, 4, 3, 2, 1]),
+    }
+}
+
+pub fn smallest(program: &[Instruction]) -> Option<i64> {
+    match paired(program) {
+        Some((min, _)) => Some(min),
+        None => search::first_accepted(program, [1, 2, 3, 4, 5, 6, 7, 8, 9]),
+    }
+}
+
+/// The smallest and largest model numbers `program` accepts, using `solve`
+/// for programs following the MONAD template and searching otherwise.
+pub fn model_numbers(program: &[Instruction]) -> Option<(i64, i64)> {
+    smallest(program).zip(largest(program))
+}
+
+pub fn part1(program: &[Instruction]) -> i64 {
+    largest(program).expect("no model number is accepted")
+}
+
+pub fn part2(program: &[Instruction]) -> i64 {
+    smallest(program).expect("no model number is accepted")
+}
+
+fn generate_block(a: i64, b: i64, c: i64) -> String {
+    format!(
+        "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\nmul y 0\n\
+         add y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\nmul y x\nadd z y\n",
+        a, b, c
+    )
+}
+
+/// A MONAD program accepting at least one model number: 7 blocks push a
+/// digit onto the base 26 stack held in `z`, and 7 pop one and compare it to
+/// their own digit. `size` is ignored.
+pub fn generate(rng: &mut Rng, _size: usize) -> String {
+    let mut stack = Vec::new();
+    let mut pushed = 0;
+    let mut program = String::new();
+
+    for _ in 0..14 {
+        if stack.is_empty() || (pushed < 7 && rng.chance(1, 2)) {
+            let c = rng.between(0, 16);
+            program += &generate_block(1, rng.between(10, 16), c);
+            stack.push(c);
+            pushed += 1;
+        } else {
+            let c = stack.pop().unwrap();
+            program += &generate_block(26, rng.between(-8, 8) - c, rng.between(0, 16));
+        }
+    }
+
+    program
 }
@@ -0,0 +1,73 @@
+use crate::block_template;
+use crate::input_blocks;
+use crate::Instruction;
+use std::fmt::Write;
+
+/// `value` as a term added to an expression: ` + 4`, ` - 10`, or nothing.
+fn offset(value: i64) -> String {
+    match value {
+        0 => String::new(),
+        value if value < 0 => format!(" - {}", value.unsigned_abs()),
+        value => format!(" + {}", value),
+    }
+}
+
+/// What a block following the MONAD template does to `z`, reading digit
+/// `w`: it pushes `w + c` onto the base 26 stack in `z` unless the top of
+/// the stack plus `b` equals `w`, after popping it if `a` is 26.
+fn template_expression(w: &str, (a, b, c): (i64, i64, i64)) -> String {
+    let popped = match a {
+        1 => "z".to_string(),
+        a => format!("z / {}", a),
+    };
+    let pushed = format!("{} * 26 + {}{}", popped, w, offset(c));
+
+    // Digits are 1 to 9, so `b` above 9 never matches one.
+    if b > 9 {
+        pushed
+    } else {
+        format!(
+            "if z % 26{} == {} {{ {} }} else {{ {} }}",
+            offset(b),
+            w,
+            popped,
+            pushed
+        )
+    }
+}
+
+/// A readable view of a MONAD program: its blocks, one per `inp`, with the
+/// `(a, b, c)` parameters and the new value of `z` for those following the
+/// usual template, and the raw instructions for the others.
+pub fn decompile(program: &[Instruction]) -> String {
+    let blocks = input_blocks(program);
+    let mut out = String::new();
+    let raw = |out: &mut String, instructions: &[Instruction]| {
+        for instruction in instructions {
+            writeln!(out, "    {}", instruction).unwrap();
+        }
+    };
+
+    let first = blocks.first().map_or(program.len(), |block| block.start);
+    if first > 0 {
+        writeln!(out, "before the first input (instructions 0..{}):", first).unwrap();
+        raw(&mut out, &program[..first]);
+    }
+
+    for (i, block) in blocks.into_iter().enumerate() {
+        let w = format!("w{}", i + 1);
+        write!(out, "block {} (instructions {:?})", i + 1, block).unwrap();
+        match block_template(&program[block.clone()]) {
+            Some((a, b, c)) => {
+                writeln!(out, ": a = {}, b = {}, c = {}", a, b, c).unwrap();
+                writeln!(out, "    z = {}", template_expression(&w, (a, b, c))).unwrap();
+            }
+            None => {
+                writeln!(out, ":").unwrap();
+                raw(&mut out, &program[block]);
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,102 @@
+use crate::Instruction;
+
+/// The values a register may hold, `lo..=hi`, for runs that do not crash.
+/// Anything that might overflow widens to all of `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: i64,
+    pub hi: i64,
+}
+
+impl Interval {
+    pub const ALL: Interval = Interval {
+        lo: i64::MIN,
+        hi: i64::MAX,
+    };
+
+    pub fn exact(value: i64) -> Self {
+        Self {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// The smallest interval holding all of `values`, or `ALL` if one of
+    /// them overflowed.
+    fn spanning(values: &[Option<i64>]) -> Self {
+        values
+            .iter()
+            .try_fold(
+                Interval {
+                    lo: i64::MAX,
+                    hi: i64::MIN,
+                },
+                |interval, value| {
+                    value.map(|value| Interval {
+                        lo: interval.lo.min(value),
+                        hi: interval.hi.max(value),
+                    })
+                },
+            )
+            .unwrap_or(Self::ALL)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        (self.lo..=self.hi).contains(&value)
+    }
+
+    pub fn constant(&self) -> Option<i64> {
+        (self.lo == self.hi).then_some(self.lo)
+    }
+
+    pub fn is_disjoint(&self, other: &Interval) -> bool {
+        self.hi < other.lo || other.hi < self.lo
+    }
+
+    /// The interval of `a op b` for `a` in `self` and `b` in `other`.
+    pub fn apply(self, instruction: Instruction, other: Interval) -> Self {
+        let (a, b) = (self, other);
+        match instruction {
+            Instruction::Inp(_) => Self::ALL,
+            Instruction::Add(..) => {
+                Self::spanning(&[a.lo.checked_add(b.lo), a.hi.checked_add(b.hi)])
+            }
+            Instruction::Mul(..) => Self::spanning(&[
+                a.lo.checked_mul(b.lo),
+                a.lo.checked_mul(b.hi),
+                a.hi.checked_mul(b.lo),
+                a.hi.checked_mul(b.hi),
+            ]),
+            Instruction::Div(..) if b.lo > 0 || b.hi < 0 => Self::spanning(&[
+                a.lo.checked_div(b.lo),
+                a.lo.checked_div(b.hi),
+                a.hi.checked_div(b.lo),
+                a.hi.checked_div(b.hi),
+            ]),
+            Instruction::Div(..) => {
+                // Dividing by anything but 0 never grows the magnitude.
+                match a.lo.checked_abs() {
+                    Some(lo) => {
+                        let magnitude = lo.max(a.hi.abs());
+                        Interval {
+                            lo: -magnitude,
+                            hi: magnitude,
+                        }
+                    }
+                    None => Self::ALL,
+                }
+            }
+            // Runs not crashing have `a >= 0` and `b > 0`.
+            Instruction::Mod(..) if a.lo >= 0 && a.hi < b.lo => a,
+            Instruction::Mod(..) => Interval {
+                lo: 0,
+                hi: a.hi.min(b.hi.saturating_sub(1)).max(0),
+            },
+            Instruction::Eql(..) => match (a.constant(), b.constant()) {
+                (Some(a), Some(b)) => Self::exact((a == b) as i64),
+                _ if a.is_disjoint(&b) => Self::exact(0),
+                _ => Interval { lo: 0, hi: 1 },
+            },
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::alu;
+use crate::alu::Registers;
+use crate::input_blocks;
+use crate::interval::Interval;
+use crate::Argument;
+use crate::Instruction;
+use crate::Variable;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Whether running `program` from `registers`, with every input a digit
+/// from 1 to 9, might end with `z` at 0. Only runs that do not crash are
+/// considered.
+fn may_accept(program: &[Instruction], registers: Registers) -> bool {
+    let mut intervals = [registers.w, registers.x, registers.y, registers.z].map(Interval::exact);
+
+    for &instruction in program {
+        match instruction {
+            Instruction::Inp(a) => intervals[a.index()] = Interval { lo: 1, hi: 9 },
+            Instruction::Add(a, b)
+            | Instruction::Mul(a, b)
+            | Instruction::Div(a, b)
+            | Instruction::Mod(a, b)
+            | Instruction::Eql(a, b) => {
+                let b = match b {
+                    Argument::Variable(variable) => intervals[variable.index()],
+                    Argument::Immediate(value) => Interval::exact(value),
+                };
+                intervals[a.index()] = intervals[a.index()].apply(instruction, b);
+            }
+        }
+    }
+
+    intervals[Variable::Z.index()].contains(0)
+}
+
+struct Search<'a> {
+    program: &'a [Instruction],
+    /// The instructions from each `inp` up to the next one.
+    segments: Vec<Range<usize>>,
+    digits: [i64; 9],
+    /// States from which no digits lead to an accepted model number.
+    dead: HashSet<(usize, Registers)>,
+}
+
+impl Search<'_> {
+    /// The digits accepted from `segment` on, last digit first.
+    fn search(&mut self, segment: usize, registers: Registers) -> Option<Vec<i64>> {
+        if segment == self.segments.len() {
+            return (registers.z == 0).then(Vec::new);
+        }
+        let range = self.segments[segment].clone();
+        if self.dead.contains(&(segment, registers))
+            || !may_accept(&self.program[range.start..], registers)
+        {
+            return None;
+        }
+
+        for digit in self.digits {
+            let Ok(next) = alu::run_from(registers, &self.program[range.clone()], [digit]) else {
+                continue;
+            };
+            if let Some(mut digits) = self.search(segment + 1, next) {
+                digits.push(digit);
+                return Some(digits);
+            }
+        }
+
+        self.dead.insert((segment, registers));
+        None
+    }
+}
+
+/// The first model number accepted by any ALU program, trying digits in the
+/// order given, found by a depth-first search over the registers at each
+/// `inp`. Branches that cannot leave `z` at 0 are cut using interval
+/// arithmetic over the rest of the program. `None` if no model number is
+/// accepted, or if the one found does not fit in an `i64`.
+pub fn first_accepted(program: &[Instruction], digits: [i64; 9]) -> Option<i64> {
+    let segments = input_blocks(program);
+    let first = segments.first()?.start;
+
+    let registers = alu::run(&program[..first], []).ok()?;
+    let mut search = Search {
+        program,
+        segments,
+        digits,
+        dead: HashSet::new(),
+    };
+
+    let digits = search.search(0, registers)?;
+    digits
+        .iter()
+        .rev()
+        .try_fold(0i64, |acc, &digit| acc.checked_mul(10)?.checked_add(digit))
+}
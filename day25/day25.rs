@@ -1,81 +1,68 @@
-use std::io;
-use std::io::BufRead;
+use common::Grid;
+use common::ParseError;
+use common::Rng;
+use common::Source;
 
-fn main() {
-    let mut map: Vec<Vec<char>> = io::stdin()
-        .lock()
-        .lines()
-        .map(|line| line.expect("error reading input").chars().collect())
+const DAY: u32 = 25;
+
+pub type Input = Grid<char>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    Grid::parse(
+        &source,
+        input,
+        |c| Some(c).filter(|c| ">v.".contains(*c)),
+        "expected `>`, `v` or `.`",
+    )
+}
+
+pub fn move_herd(map: &mut Grid<char>, herd: char, dx: isize, dy: isize) -> bool {
+    let wrapping = map.wrapping();
+    let moves: Vec<((usize, usize), (usize, usize))> = map
+        .positions()
+        .filter(|&p| map[p] == herd)
+        .map(|(x, y)| ((x, y), wrapping.position(x as isize + dx, y as isize + dy)))
+        .filter(|&(_, to)| map[to] == '.')
         .collect();
 
-    let height = map.len();
-    let width = map[0].len();
+    for &(from, to) in &moves {
+        map[from] = '.';
+        map[to] = herd;
+    }
 
-    for step in 1.. {
-        let mut moved = false;
+    !moves.is_empty()
+}
 
-        for y in 0..height {
-            let mut x = 0;
-            let mut clear_first = false;
+pub fn part1(map: &Grid<char>) -> usize {
+    let mut map = map.clone();
 
-            while x < width {
-                if map[y][x] == '>' {
-                    let nx = (x + 1) % width;
-                    if map[y][nx] == '.' {
-                        if x > 0 {
-                            map[y][x] = '.';
-                        } else {
-                            clear_first = true;
-                        }
-                        map[y][nx] = '>';
-                        moved = true;
-                        x += 2;
-                    } else {
-                        x += 1;
-                    }
-                } else {
-                    x += 1;
-                }
-            }
+    for step in 1.. {
+        let east = move_herd(&mut map, '>', 1, 0);
+        let south = move_herd(&mut map, 'v', 0, 1);
 
-            if clear_first {
-                map[y][0] = '.';
-            }
+        if !east && !south {
+            return step;
         }
+    }
 
-        for x in 0..width {
-            let mut y = 0;
-            let mut clear_first = false;
-
-            while y < height {
-                if map[y][x] == 'v' {
-                    let ny = (y + 1) % height;
-                    if map[ny][x] == '.' {
-                        if y > 0 {
-                            map[y][x] = '.';
-                        } else {
-                            clear_first = true;
-                        }
+    unreachable!()
+}
 
-                        map[ny][x] = 'v';
-                        moved = true;
-                        y += 2;
-                    } else {
-                        y += 1;
-                    }
-                } else {
-                    y += 1;
-                }
-            }
+/// A `size` by `size` map of sea cucumbers that eventually stop moving.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
 
-            if clear_first {
-                map[0][x] = '.';
-            }
-        }
+    loop {
+        let map = Grid::from_fn(size, size, |_, _| *rng.pick(&['>', 'v', '.']));
 
-        if !moved {
-            println!("part 1: {}", step);
-            break;
+        let mut simulated = map.clone();
+        if (0..10_000).any(|_| {
+            let east = move_herd(&mut simulated, '>', 1, 0);
+            let south = move_herd(&mut simulated, 'v', 0, 1);
+            !east && !south
+        }) {
+            return map.to_string() + "\n";
         }
     }
 }